@@ -0,0 +1,229 @@
+use std::collections::{HashMap, VecDeque};
+
+use chrono::{DateTime, Utc};
+use petgraph::graph::NodeIndex;
+use petgraph::{Direction, EdgeType};
+use serde::{Deserialize, Serialize};
+
+use crate::models::energy::{EnergyPolicy, EnergySpec};
+use crate::models::graph_models::Grapho;
+use crate::models::node_types::NodeTypes;
+
+const MAX_ITERATIONS: usize = 100;
+const TOLERANCE: f64 = 1e-10;
+
+/// Measure used to score nodes; the one configured on a `Grapho` drives `energy`.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Centrality {
+    /// Number of entries in `Node::connections`.
+    #[default]
+    Degree,
+    PageRank {
+        damping: f64,
+    },
+    Eigenvector,
+    Betweenness,
+    Closeness,
+}
+
+impl<T: NodeTypes, Ty: EdgeType> EnergyPolicy<T, Ty> for Centrality
+where
+    T: Clone + Serialize + for<'de> Deserialize<'de>,
+    Ty: EdgeType + Serialize + for<'de> Deserialize<'de>,
+{
+    // Grapho only asks global measures for `node_energies`, once per refresh;
+    // scoring a single node still takes the whole graph.
+    fn node_energy(&self, graph: &Grapho<T, Ty>, node: NodeIndex, now: DateTime<Utc>) -> f64 {
+        match self {
+            Centrality::Degree => graph.core[node].connections.len() as f64,
+            _ => self.node_energies(graph, now)[node.index()],
+        }
+    }
+
+    fn node_energies(&self, graph: &Grapho<T, Ty>, _now: DateTime<Utc>) -> Vec<f64> {
+        let scores = graph.centrality(*self);
+        graph.core.node_indices().map(|idx| scores[&idx]).collect()
+    }
+
+    fn is_local(&self) -> bool {
+        *self == Centrality::Degree
+    }
+
+    fn spec(&self) -> EnergySpec {
+        EnergySpec::Centrality(*self)
+    }
+}
+
+impl<T: NodeTypes, Ty: EdgeType> Grapho<T, Ty>
+where
+    T: Clone + Serialize + for<'de> Deserialize<'de>,
+    Ty: EdgeType + Serialize + for<'de> Deserialize<'de>,
+{
+    pub fn centrality(&self, measure: Centrality) -> HashMap<NodeIndex, f64> {
+        let scores = match measure {
+            Centrality::Degree => self
+                .core
+                .node_indices()
+                .map(|idx| self.core[idx].connections.len() as f64)
+                .collect(),
+            Centrality::PageRank { damping } => self.page_rank_scores(damping),
+            Centrality::Eigenvector => self.eigenvector_scores(),
+            Centrality::Betweenness => self.betweenness_scores(),
+            Centrality::Closeness => self.closeness_scores(),
+        };
+        self.core.node_indices().zip(scores).collect()
+    }
+
+    pub fn pagerank(&self, damping: f64) -> HashMap<NodeIndex, f64> {
+        self.centrality(Centrality::PageRank { damping })
+    }
+
+    pub fn eigenvector_centrality(&self) -> HashMap<NodeIndex, f64> {
+        self.centrality(Centrality::Eigenvector)
+    }
+
+    pub fn betweenness_centrality(&self) -> HashMap<NodeIndex, f64> {
+        self.centrality(Centrality::Betweenness)
+    }
+
+    pub fn closeness_centrality(&self) -> HashMap<NodeIndex, f64> {
+        self.centrality(Centrality::Closeness)
+    }
+
+    /// Selects the measure that drives `energy` and recomputes it for every node.
+    pub fn set_energy_measure(&mut self, measure: Centrality) {
+        self.set_energy_policy(measure);
+    }
+
+    fn page_rank_scores(&self, damping: f64) -> Vec<f64> {
+        let n = self.core.node_count();
+        if n == 0 {
+            return vec![];
+        }
+        let out_degree: Vec<usize> = self
+            .core
+            .node_indices()
+            .map(|idx| self.core.neighbors(idx).count())
+            .collect();
+        let mut rank = vec![1.0 / n as f64; n];
+        for _ in 0..MAX_ITERATIONS {
+            let dangling: f64 = (0..n)
+                .filter(|&i| out_degree[i] == 0)
+                .map(|i| rank[i])
+                .sum();
+            let base = (1.0 - damping) / n as f64 + damping * dangling / n as f64;
+            let mut next = vec![base; n];
+            for idx in self.core.node_indices() {
+                let i = idx.index();
+                if out_degree[i] == 0 {
+                    continue;
+                }
+                let share = damping * rank[i] / out_degree[i] as f64;
+                for target in self.core.neighbors(idx) {
+                    next[target.index()] += share;
+                }
+            }
+            let delta: f64 = next.iter().zip(&rank).map(|(a, b)| (a - b).abs()).sum();
+            rank = next;
+            if delta < TOLERANCE {
+                break;
+            }
+        }
+        rank
+    }
+
+    // Power iteration on (A + I) so bipartite graphs converge instead of oscillating.
+    fn eigenvector_scores(&self) -> Vec<f64> {
+        let n = self.core.node_count();
+        let mut x = vec![1.0 / n as f64; n];
+        for _ in 0..MAX_ITERATIONS {
+            let mut next = x.clone();
+            for idx in self.core.node_indices() {
+                for source in self.core.neighbors_directed(idx, Direction::Incoming) {
+                    next[idx.index()] += x[source.index()];
+                }
+            }
+            let norm = next.iter().map(|v| v * v).sum::<f64>().sqrt();
+            if norm == 0.0 {
+                return next;
+            }
+            next.iter_mut().for_each(|v| *v /= norm);
+            let delta: f64 = next.iter().zip(&x).map(|(a, b)| (a - b).abs()).sum();
+            x = next;
+            if delta < TOLERANCE {
+                break;
+            }
+        }
+        x
+    }
+
+    // Brandes' algorithm over unweighted shortest paths.
+    fn betweenness_scores(&self) -> Vec<f64> {
+        let n = self.core.node_count();
+        let mut scores = vec![0.0; n];
+        for s in self.core.node_indices() {
+            let mut stack = Vec::new();
+            let mut preds: Vec<Vec<usize>> = vec![Vec::new(); n];
+            let mut sigma = vec![0.0; n];
+            let mut dist = vec![-1i64; n];
+            sigma[s.index()] = 1.0;
+            dist[s.index()] = 0;
+            let mut queue = VecDeque::from([s]);
+            while let Some(v) = queue.pop_front() {
+                stack.push(v.index());
+                for w in self.core.neighbors(v) {
+                    let (v, w) = (v.index(), w.index());
+                    if dist[w] < 0 {
+                        dist[w] = dist[v] + 1;
+                        queue.push_back(NodeIndex::new(w));
+                    }
+                    if dist[w] == dist[v] + 1 && !preds[w].contains(&v) {
+                        sigma[w] += sigma[v];
+                        preds[w].push(v);
+                    }
+                }
+            }
+            let mut delta = vec![0.0; n];
+            while let Some(w) = stack.pop() {
+                for &v in &preds[w] {
+                    delta[v] += sigma[v] / sigma[w] * (1.0 + delta[w]);
+                }
+                if w != s.index() {
+                    scores[w] += delta[w];
+                }
+            }
+        }
+        if !self.core.is_directed() {
+            scores.iter_mut().for_each(|v| *v /= 2.0);
+        }
+        scores
+    }
+
+    // Scaled by the reachable fraction so disconnected graphs stay comparable.
+    fn closeness_scores(&self) -> Vec<f64> {
+        let n = self.core.node_count();
+        self.core
+            .node_indices()
+            .map(|s| {
+                let mut dist = vec![usize::MAX; n];
+                dist[s.index()] = 0;
+                let mut queue = VecDeque::from([s]);
+                let (mut reached, mut total) = (0usize, 0usize);
+                while let Some(v) = queue.pop_front() {
+                    for w in self.core.neighbors(v) {
+                        if dist[w.index()] == usize::MAX {
+                            dist[w.index()] = dist[v.index()] + 1;
+                            reached += 1;
+                            total += dist[w.index()];
+                            queue.push_back(w);
+                        }
+                    }
+                }
+                if total == 0 {
+                    return 0.0;
+                }
+                (reached as f64 / total as f64) * (reached as f64 / (n - 1) as f64)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,174 @@
+use std::collections::{BTreeMap, HashMap};
+
+use petgraph::EdgeType;
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+use crate::models::graph_models::Grapho;
+use crate::models::node_types::NodeTypes;
+
+const MAX_ITERATIONS: usize = 100;
+
+// Symmetric weighted adjacency; a self-loop adds twice its weight to the diagonal.
+type Adjacency = Vec<BTreeMap<usize, f64>>;
+
+impl<T: NodeTypes, Ty: EdgeType> Grapho<T, Ty>
+where
+    T: Clone + Serialize + for<'de> Deserialize<'de>,
+    Ty: EdgeType + Serialize + for<'de> Deserialize<'de>,
+{
+    /// Louvain modularity optimisation over `core`, ignoring direction and using
+    /// the edge weights. Communities are numbered from 0 in node order.
+    pub fn louvain_communities(&self) -> HashMap<NodeIndex, usize> {
+        let mut adjacency = self.weighted_adjacency();
+        let mut membership: Vec<usize> = (0..adjacency.len()).collect();
+        loop {
+            let (communities, improved) = louvain_level(&adjacency);
+            if !improved {
+                break;
+            }
+            let (communities, count) = relabel(&communities);
+            for c in membership.iter_mut() {
+                *c = communities[*c];
+            }
+            adjacency = aggregate(&adjacency, &communities, count);
+        }
+        self.community_map(&relabel(&membership).0)
+    }
+
+    /// Label propagation with visit order and ties drawn from `seed`, so the
+    /// same seed always yields the same communities.
+    pub fn label_propagation_communities(&self, seed: u64) -> HashMap<NodeIndex, usize> {
+        let adjacency = self.weighted_adjacency();
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut labels: Vec<usize> = (0..adjacency.len()).collect();
+        let mut order: Vec<usize> = (0..adjacency.len()).collect();
+        for _ in 0..MAX_ITERATIONS {
+            let mut changed = false;
+            order.shuffle(&mut rng);
+            for &i in &order {
+                let mut weights: BTreeMap<usize, f64> = BTreeMap::new();
+                for (&j, &w) in adjacency[i].iter().filter(|(j, _)| **j != i) {
+                    *weights.entry(labels[j]).or_default() += w;
+                }
+                let Some(max) = weights.values().copied().reduce(f64::max) else {
+                    continue;
+                };
+                if weights.get(&labels[i]) == Some(&max) {
+                    continue;
+                }
+                let best: Vec<usize> = weights
+                    .iter()
+                    .filter(|(_, w)| **w == max)
+                    .map(|(label, _)| *label)
+                    .collect();
+                if let Some(&label) = best.choose(&mut rng) {
+                    labels[i] = label;
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+        self.community_map(&relabel(&labels).0)
+    }
+
+    /// Writes the community ids into `Node::metadata` so they are saved with the graph.
+    pub fn assign_communities(&mut self, communities: &HashMap<NodeIndex, usize>) {
+        for (&idx, &community) in communities {
+            if let Some(node) = self.core.node_weight_mut(idx) {
+                node.metadata.community = Some(community);
+                node.metadata.update();
+            }
+        }
+    }
+
+    fn weighted_adjacency(&self) -> Adjacency {
+        let mut adjacency = vec![BTreeMap::new(); self.core.node_count()];
+        for e in self.core.edge_references() {
+            let (a, b, w) = (e.source().index(), e.target().index(), *e.weight() as f64);
+            *adjacency[a].entry(b).or_default() += w;
+            *adjacency[b].entry(a).or_default() += w;
+        }
+        adjacency
+    }
+
+    fn community_map(&self, communities: &[usize]) -> HashMap<NodeIndex, usize> {
+        self.core
+            .node_indices()
+            .map(|idx| (idx, communities[idx.index()]))
+            .collect()
+    }
+}
+
+// One pass of local moves; returns the community of every node and whether any moved.
+fn louvain_level(adjacency: &Adjacency) -> (Vec<usize>, bool) {
+    let n = adjacency.len();
+    let degree: Vec<f64> = adjacency.iter().map(|row| row.values().sum()).collect();
+    let total: f64 = degree.iter().sum();
+    let mut communities: Vec<usize> = (0..n).collect();
+    if total == 0.0 {
+        return (communities, false);
+    }
+    let mut community_degree = degree.clone();
+    let mut improved = false;
+    for _ in 0..MAX_ITERATIONS {
+        let mut moved = false;
+        for i in 0..n {
+            let current = communities[i];
+            let mut links: BTreeMap<usize, f64> = BTreeMap::new();
+            for (&j, &w) in adjacency[i].iter().filter(|(j, _)| **j != i) {
+                *links.entry(communities[j]).or_default() += w;
+            }
+            community_degree[current] -= degree[i];
+            let gain = |c: usize, w: f64| w - community_degree[c] * degree[i] / total;
+            let mut best = current;
+            let mut best_gain = gain(current, links.get(&current).copied().unwrap_or(0.0));
+            for (&c, &w) in &links {
+                let g = gain(c, w);
+                if g > best_gain + 1e-12 {
+                    best = c;
+                    best_gain = g;
+                }
+            }
+            community_degree[best] += degree[i];
+            if best != current {
+                communities[i] = best;
+                moved = true;
+                improved = true;
+            }
+        }
+        if !moved {
+            break;
+        }
+    }
+    (communities, improved)
+}
+
+fn aggregate(adjacency: &Adjacency, communities: &[usize], count: usize) -> Adjacency {
+    let mut next = vec![BTreeMap::new(); count];
+    for (i, row) in adjacency.iter().enumerate() {
+        for (&j, &w) in row {
+            *next[communities[i]].entry(communities[j]).or_default() += w;
+        }
+    }
+    next
+}
+
+// Renumbers labels to 0..count in order of first appearance.
+fn relabel(labels: &[usize]) -> (Vec<usize>, usize) {
+    let mut ids = HashMap::new();
+    let relabeled = labels
+        .iter()
+        .map(|label| {
+            let next = ids.len();
+            *ids.entry(*label).or_insert(next)
+        })
+        .collect();
+    (relabeled, ids.len())
+}
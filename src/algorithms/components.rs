@@ -0,0 +1,79 @@
+use std::collections::{HashSet, VecDeque};
+
+use petgraph::EdgeType;
+use petgraph::graph::NodeIndex;
+use serde::{Deserialize, Serialize};
+
+use crate::models::graph_models::{Grapho, Ref};
+use crate::models::node_types::NodeTypes;
+
+impl<T: NodeTypes, Ty: EdgeType> Grapho<T, Ty>
+where
+    T: Clone + Serialize + for<'de> Deserialize<'de>,
+    Ty: EdgeType + Serialize + for<'de> Deserialize<'de>,
+{
+    /// Strongly connected components for directed graphs, connected components
+    /// otherwise. Largest component first.
+    pub fn components(&self) -> Vec<Vec<Ref<NodeIndex>>> {
+        if self.core.is_directed() {
+            self.strongly_connected_components()
+        } else {
+            self.weakly_connected_components()
+        }
+    }
+
+    pub fn component_sizes(&self) -> Vec<usize> {
+        self.components().iter().map(Vec::len).collect()
+    }
+
+    /// Components ignoring edge direction.
+    pub fn weakly_connected_components(&self) -> Vec<Vec<Ref<NodeIndex>>> {
+        let mut seen = HashSet::new();
+        let mut groups = Vec::new();
+        for start in self.core.node_indices() {
+            if !seen.insert(start) {
+                continue;
+            }
+            let mut group = vec![start];
+            let mut queue = VecDeque::from([start]);
+            while let Some(current) = queue.pop_front() {
+                for next in self.core.neighbors_undirected(current) {
+                    if seen.insert(next) {
+                        group.push(next);
+                        queue.push_back(next);
+                    }
+                }
+            }
+            groups.push(group);
+        }
+        self.component_refs(groups)
+    }
+
+    pub fn strongly_connected_components(&self) -> Vec<Vec<Ref<NodeIndex>>> {
+        self.component_refs(petgraph::algo::tarjan_scc(&self.core))
+    }
+
+    /// One new `Grapho` per component, named `<graph>-component-<n>`.
+    pub fn split_components(&self) -> Vec<Self> {
+        self.components()
+            .iter()
+            .enumerate()
+            .map(|(i, group)| {
+                let nodes: HashSet<NodeIndex> = group.iter().map(|r| r.index).collect();
+                self.extract(&format!("{}-component-{}", self.name, i), &nodes, |_| true)
+            })
+            .collect()
+    }
+
+    fn component_refs(&self, groups: Vec<Vec<NodeIndex>>) -> Vec<Vec<Ref<NodeIndex>>> {
+        let mut groups: Vec<Vec<Ref<NodeIndex>>> = groups
+            .into_iter()
+            .map(|mut group| {
+                group.sort();
+                group.into_iter().map(|idx| self.node_ref(idx)).collect()
+            })
+            .collect();
+        groups.sort_by(|a, b| b.len().cmp(&a.len()).then(a[0].index.cmp(&b[0].index)));
+        groups
+    }
+}
@@ -0,0 +1,72 @@
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+
+use petgraph::Directed;
+use petgraph::graph::NodeIndex;
+use serde::{Deserialize, Serialize};
+
+use crate::models::graph_models::{Grapho, Ref};
+use crate::models::node_types::NodeTypes;
+
+impl<T: NodeTypes> Grapho<T, Directed>
+where
+    T: Clone + Serialize + for<'de> Deserialize<'de>,
+{
+    pub fn has_cycle(&self) -> bool {
+        petgraph::algo::is_cyclic_directed(&self.core)
+    }
+
+    /// One shortest cycle per strongly connected component that contains a cycle,
+    /// each starting and ending at the same node.
+    pub fn find_cycles(&self) -> Vec<Vec<Ref<NodeIndex>>> {
+        petgraph::algo::tarjan_scc(&self.core)
+            .into_iter()
+            .filter_map(|scc| scc.into_iter().min())
+            .filter_map(|start| self.cycle_through(start))
+            .map(|cycle| cycle.into_iter().map(|idx| self.node_ref(idx)).collect())
+            .collect()
+    }
+
+    /// Dependencies first: every edge `from -> to` puts `from` before `to`.
+    pub fn topological_order(&self) -> Result<Vec<Ref<NodeIndex>>, String> {
+        match petgraph::algo::toposort(&self.core, None) {
+            Ok(order) => Ok(order.into_iter().map(|idx| self.node_ref(idx)).collect()),
+            Err(cycle) => {
+                let path = self
+                    .cycle_through(cycle.node_id())
+                    .unwrap_or_else(|| vec![cycle.node_id()])
+                    .into_iter()
+                    .map(|idx| self.core[idx].name.clone())
+                    .collect::<Vec<_>>()
+                    .join(" -> ");
+                Err(format!("Error to sort graph: cycle detected: {}", path))
+            }
+        }
+    }
+
+    // Shortest path from `start` back to itself, found by BFS over outgoing edges.
+    fn cycle_through(&self, start: NodeIndex) -> Option<Vec<NodeIndex>> {
+        let mut parent: HashMap<NodeIndex, NodeIndex> = HashMap::new();
+        let mut queue = VecDeque::from([start]);
+        while let Some(current) = queue.pop_front() {
+            for next in self.core.neighbors(current) {
+                if next == start {
+                    let mut path = vec![start];
+                    let mut node = current;
+                    while node != start {
+                        path.push(node);
+                        node = parent[&node];
+                    }
+                    path[1..].reverse();
+                    path.push(start);
+                    return Some(path);
+                }
+                if let Entry::Vacant(e) = parent.entry(next) {
+                    e.insert(current);
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+}
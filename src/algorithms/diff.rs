@@ -0,0 +1,285 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
+
+use petgraph::EdgeType;
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::graph_models::{Grapho, Node, Ref};
+use crate::models::node_types::NodeTypes;
+
+/// Structured difference between two versions of a graph. Node refs in
+/// `added_nodes` point into the newer graph, everything removed into the older one.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GraphDiff {
+    pub added_nodes: Vec<Ref<NodeIndex>>,
+    pub removed_nodes: Vec<Ref<NodeIndex>>,
+    pub changed_nodes: Vec<NodeChange>,
+    pub added_connections: Vec<ConnectionKey>,
+    pub removed_connections: Vec<ConnectionKey>,
+    pub edge_kind_changes: Vec<EdgeKindChange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeChange {
+    pub id: Uuid,
+    pub name: String,
+    pub fields: Vec<FieldChange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: String,
+    pub after: String,
+}
+
+/// A connection identified by endpoint names and edge kind; endpoints are
+/// ordered by name for undirected graphs.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ConnectionKey {
+    pub from: String,
+    pub to: String,
+    pub edge_kind: String,
+}
+
+/// `None` on one side means the edge kind only exists on the other.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EdgeKindChange {
+    pub name: String,
+    pub before: Option<f64>,
+    pub after: Option<f64>,
+}
+
+impl GraphDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.changed_nodes.is_empty()
+            && self.added_connections.is_empty()
+            && self.removed_connections.is_empty()
+            && self.edge_kind_changes.is_empty()
+    }
+}
+
+impl<T: NodeTypes, Ty: EdgeType> Grapho<T, Ty>
+where
+    T: Clone + Serialize + for<'de> Deserialize<'de>,
+    Ty: EdgeType + Serialize + for<'de> Deserialize<'de>,
+{
+    /// Changes needed to go from `self` to `other`. Nodes are matched by
+    /// `metadata.id` first and by `name` for the ones left over.
+    pub fn diff(&self, other: &Self) -> GraphDiff {
+        let matches = self.match_nodes(other);
+        let matched_new: HashSet<NodeIndex> = matches.values().copied().collect();
+        let mut diff = GraphDiff {
+            removed_nodes: self
+                .core
+                .node_indices()
+                .filter(|idx| !matches.contains_key(idx))
+                .map(|idx| self.node_ref(idx))
+                .collect(),
+            added_nodes: other
+                .core
+                .node_indices()
+                .filter(|idx| !matched_new.contains(idx))
+                .map(|idx| other.node_ref(idx))
+                .collect(),
+            ..GraphDiff::default()
+        };
+
+        let mut pairs: Vec<_> = matches.iter().collect();
+        pairs.sort();
+        for (&old, &new) in pairs {
+            let fields = node_field_changes(&self.core[old], &other.core[new]);
+            if !fields.is_empty() {
+                diff.changed_nodes.push(NodeChange {
+                    id: other.core[new].metadata.id,
+                    name: other.core[new].name.clone(),
+                    fields,
+                });
+            }
+        }
+
+        // old connections are renamed to the matched node so renames are not reported twice
+        let renamed: HashMap<NodeIndex, &str> = matches
+            .iter()
+            .map(|(&old, &new)| (old, other.core[new].name.as_str()))
+            .collect();
+        let before = self.connection_counts(|idx| {
+            renamed
+                .get(&idx)
+                .map(|n| n.to_string())
+                .unwrap_or_else(|| self.core[idx].name.clone())
+        });
+        let after = other.connection_counts(|idx| other.core[idx].name.clone());
+        diff.removed_connections = count_difference(&before, &after);
+        diff.added_connections = count_difference(&after, &before);
+
+        let old_kinds: BTreeMap<&str, f64> = self
+            .edge_kinds
+            .iter()
+            .map(|k| (k.name.as_str(), k.energy))
+            .collect();
+        let new_kinds: BTreeMap<&str, f64> = other
+            .edge_kinds
+            .iter()
+            .map(|k| (k.name.as_str(), k.energy))
+            .collect();
+        let names: BTreeSet<&str> = old_kinds.keys().chain(new_kinds.keys()).copied().collect();
+        for name in names {
+            let (before, after) = (old_kinds.get(name).copied(), new_kinds.get(name).copied());
+            if before != after {
+                diff.edge_kind_changes.push(EdgeKindChange {
+                    name: name.to_string(),
+                    before,
+                    after,
+                });
+            }
+        }
+        diff
+    }
+
+    // old index -> new index
+    fn match_nodes(&self, other: &Self) -> HashMap<NodeIndex, NodeIndex> {
+        let by_id: HashMap<Uuid, NodeIndex> = other
+            .core
+            .node_indices()
+            .map(|idx| (other.core[idx].metadata.id, idx))
+            .collect();
+        let mut matches = HashMap::new();
+        let mut taken = HashSet::new();
+        for idx in self.core.node_indices() {
+            if let Some(&new) = by_id.get(&self.core[idx].metadata.id) {
+                matches.insert(idx, new);
+                taken.insert(new);
+            }
+        }
+        for idx in self.core.node_indices() {
+            if matches.contains_key(&idx) {
+                continue;
+            }
+            if let Some(new) = other
+                .get_node_index_by_name(&self.core[idx].name)
+                .filter(|new| !taken.contains(new))
+            {
+                matches.insert(idx, new);
+                taken.insert(new);
+            }
+        }
+        matches
+    }
+
+    fn connection_counts<F>(&self, name_of: F) -> BTreeMap<ConnectionKey, usize>
+    where
+        F: Fn(NodeIndex) -> String,
+    {
+        let kinds: HashMap<_, &str> = self
+            .runtime_ref
+            .edges
+            .iter()
+            .map(|r| (r.index, r.name.as_str()))
+            .collect();
+        let mut counts = BTreeMap::new();
+        for e in self.core.edge_references() {
+            let (mut from, mut to) = (name_of(e.source()), name_of(e.target()));
+            if !self.core.is_directed() && from > to {
+                std::mem::swap(&mut from, &mut to);
+            }
+            let key = ConnectionKey {
+                from,
+                to,
+                edge_kind: kinds.get(&e.id()).copied().unwrap_or_default().to_string(),
+            };
+            *counts.entry(key).or_default() += 1;
+        }
+        counts
+    }
+}
+
+fn node_field_changes<T: Serialize>(before: &Node<T>, after: &Node<T>) -> Vec<FieldChange> {
+    let candidates = [
+        ("name", render(&before.name), render(&after.name)),
+        ("content", render(&before.content), render(&after.content)),
+        (
+            "description",
+            render(&before.metadata.description),
+            render(&after.metadata.description),
+        ),
+        (
+            "embedding",
+            render(&before.embedding),
+            render(&after.embedding),
+        ),
+        ("labels", render(&before.labels), render(&after.labels)),
+        ("props", render(&before.props), render(&after.props)),
+    ];
+    candidates
+        .into_iter()
+        .filter(|(_, b, a)| b != a)
+        .map(|(field, before, after)| FieldChange {
+            field: field.to_string(),
+            before,
+            after,
+        })
+        .collect()
+}
+
+fn render<S: Serialize + ?Sized>(value: &S) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+fn count_difference(
+    left: &BTreeMap<ConnectionKey, usize>,
+    right: &BTreeMap<ConnectionKey, usize>,
+) -> Vec<ConnectionKey> {
+    left.iter()
+        .flat_map(|(key, &count)| {
+            let extra = count.saturating_sub(right.get(key).copied().unwrap_or(0));
+            std::iter::repeat_n(key.clone(), extra)
+        })
+        .collect()
+}
+
+impl fmt::Display for GraphDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "No differences.");
+        }
+        for node in &self.added_nodes {
+            writeln!(f, "+ node {} ({})", node.name, node.uuid)?;
+        }
+        for node in &self.removed_nodes {
+            writeln!(f, "- node {} ({})", node.name, node.uuid)?;
+        }
+        for node in &self.changed_nodes {
+            writeln!(f, "~ node {} ({})", node.name, node.id)?;
+            for field in &node.fields {
+                writeln!(
+                    f,
+                    "    {}: {} -> {}",
+                    field.field, field.before, field.after
+                )?;
+            }
+        }
+        for c in &self.added_connections {
+            writeln!(f, "+ connection {} -[{}]- {}", c.from, c.edge_kind, c.to)?;
+        }
+        for c in &self.removed_connections {
+            writeln!(f, "- connection {} -[{}]- {}", c.from, c.edge_kind, c.to)?;
+        }
+        for k in &self.edge_kind_changes {
+            let show = |v: Option<f64>| v.map_or("-".to_string(), |v| format!("{:.2}", v));
+            writeln!(
+                f,
+                "~ edge kind {}: energy {} -> {}",
+                k.name,
+                show(k.before),
+                show(k.after)
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,233 @@
+use std::collections::{HashMap, HashSet};
+
+use petgraph::EdgeType;
+use petgraph::graph::NodeIndex;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::models::graph_models::Grapho;
+use crate::models::node_types::NodeTypes;
+
+/// node2vec walk parameters: `p` controls returning to the previous node,
+/// `q` moving away from it (q < 1 explores outward, q > 1 stays local).
+#[derive(Debug, Clone)]
+pub struct WalkConfig {
+    pub walk_length: usize,
+    pub walks_per_node: usize,
+    pub p: f64,
+    pub q: f64,
+    pub seed: u64,
+    pub edge_kind: Option<String>, // only follow connections of this kind
+    pub label: Option<String>,     // only start from and step onto nodes with this label
+}
+
+impl Default for WalkConfig {
+    fn default() -> Self {
+        Self {
+            walk_length: 20,
+            walks_per_node: 10,
+            p: 1.0,
+            q: 1.0,
+            seed: 42,
+            edge_kind: None,
+            label: None,
+        }
+    }
+}
+
+/// Skip-gram with negative sampling over the generated walks.
+#[derive(Debug, Clone)]
+pub struct EmbeddingConfig {
+    pub dimensions: usize,
+    pub window: usize,
+    pub negative_samples: usize,
+    pub epochs: usize,
+    pub learning_rate: f32,
+    pub seed: u64,
+}
+
+impl Default for EmbeddingConfig {
+    fn default() -> Self {
+        Self {
+            dimensions: 32,
+            window: 5,
+            negative_samples: 5,
+            epochs: 5,
+            learning_rate: 0.025,
+            seed: 42,
+        }
+    }
+}
+
+impl<T: NodeTypes, Ty: EdgeType> Grapho<T, Ty>
+where
+    T: Clone + Serialize + for<'de> Deserialize<'de>,
+    Ty: EdgeType + Serialize + for<'de> Deserialize<'de>,
+{
+    /// Biased second-order walks along `outgoing` connections, starting
+    /// `walks_per_node` times from every node. Walks stop early at dead ends.
+    pub fn random_walks(&self, config: &WalkConfig) -> Vec<Vec<NodeIndex>> {
+        let allowed = |idx: NodeIndex| {
+            config
+                .label
+                .as_ref()
+                .is_none_or(|l| self.core[idx].labels.contains(l))
+        };
+        let neighbors: Vec<Vec<NodeIndex>> = self
+            .core
+            .node_indices()
+            .map(|idx| {
+                self.outgoing(idx)
+                    .into_iter()
+                    .filter(|c| config.edge_kind.as_ref().is_none_or(|k| &c.edge.name == k))
+                    .filter(|c| allowed(c.node.index))
+                    .map(|c| c.node.index)
+                    .collect()
+            })
+            .collect();
+        let neighbor_sets: Vec<HashSet<NodeIndex>> = neighbors
+            .iter()
+            .map(|n| n.iter().copied().collect())
+            .collect();
+        let mut rng = StdRng::seed_from_u64(config.seed);
+        let mut walks = Vec::with_capacity(self.core.node_count() * config.walks_per_node);
+        for _ in 0..config.walks_per_node {
+            for start in self.core.node_indices().filter(|&idx| allowed(idx)) {
+                let mut walk = vec![start];
+                while walk.len() < config.walk_length {
+                    let current = walk[walk.len() - 1];
+                    let candidates = &neighbors[current.index()];
+                    if candidates.is_empty() {
+                        break;
+                    }
+                    let next = match walk.len().checked_sub(2).map(|i| walk[i]) {
+                        None => candidates[rng.gen_range(0..candidates.len())],
+                        Some(previous) => {
+                            let weights: Vec<f64> = candidates
+                                .iter()
+                                .map(|&x| {
+                                    if x == previous {
+                                        1.0 / config.p
+                                    } else if neighbor_sets[previous.index()].contains(&x) {
+                                        1.0
+                                    } else {
+                                        1.0 / config.q
+                                    }
+                                })
+                                .collect();
+                            candidates[sample_weighted(&weights, &mut rng)]
+                        }
+                    };
+                    walk.push(next);
+                }
+                walks.push(walk);
+            }
+        }
+        walks
+    }
+
+    /// node2vec: random walks followed by skip-gram training, one vector per node.
+    pub fn node2vec(
+        &self,
+        walks: &WalkConfig,
+        embedding: &EmbeddingConfig,
+    ) -> HashMap<NodeIndex, Vec<f32>> {
+        let walks = self.random_walks(walks);
+        let vectors = train_skip_gram(&walks, self.core.node_count(), embedding);
+        self.core.node_indices().zip(vectors).collect()
+    }
+}
+
+/// Trains skip-gram vectors for nodes `0..node_count` from `walks`.
+pub fn train_skip_gram(
+    walks: &[Vec<NodeIndex>],
+    node_count: usize,
+    config: &EmbeddingConfig,
+) -> Vec<Vec<f32>> {
+    let dim = config.dimensions;
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let mut input: Vec<Vec<f32>> = (0..node_count)
+        .map(|_| {
+            (0..dim)
+                .map(|_| (rng.r#gen::<f32>() - 0.5) / dim as f32)
+                .collect()
+        })
+        .collect();
+    let mut output = vec![vec![0.0f32; dim]; node_count];
+
+    // Negatives are drawn from the unigram distribution raised to 3/4.
+    let mut frequency = vec![0.0f64; node_count];
+    walks
+        .iter()
+        .flatten()
+        .for_each(|n| frequency[n.index()] += 1.0);
+    let noise: Vec<f64> = frequency
+        .iter()
+        .scan(0.0, |acc, f| {
+            *acc += f.powf(0.75);
+            Some(*acc)
+        })
+        .collect();
+    let Some(&noise_total) = noise.last().filter(|t| **t > 0.0) else {
+        return input;
+    };
+
+    let mut gradient = vec![0.0f32; dim];
+    for epoch in 0..config.epochs {
+        let lr = config.learning_rate * (1.0 - epoch as f32 / config.epochs as f32).max(0.0001);
+        for walk in walks {
+            for (pos, center) in walk.iter().enumerate() {
+                let from = pos.saturating_sub(config.window);
+                let to = (pos + config.window + 1).min(walk.len());
+                for (ctx_pos, context) in walk.iter().enumerate().take(to).skip(from) {
+                    if ctx_pos == pos {
+                        continue;
+                    }
+                    let center = center.index();
+                    gradient.iter_mut().for_each(|g| *g = 0.0);
+                    let mut update = |target: usize, label: f32| {
+                        let dot: f32 = input[center]
+                            .iter()
+                            .zip(&output[target])
+                            .map(|(a, b)| a * b)
+                            .sum();
+                        let g = (label - sigmoid(dot)) * lr;
+                        for d in 0..dim {
+                            gradient[d] += g * output[target][d];
+                            output[target][d] += g * input[center][d];
+                        }
+                    };
+                    update(context.index(), 1.0);
+                    for _ in 0..config.negative_samples {
+                        let draw = rng.r#gen::<f64>() * noise_total;
+                        let negative = noise.partition_point(|c| *c <= draw).min(node_count - 1);
+                        if negative != context.index() {
+                            update(negative, 0.0);
+                        }
+                    }
+                    for d in 0..dim {
+                        input[center][d] += gradient[d];
+                    }
+                }
+            }
+        }
+    }
+    input
+}
+
+fn sigmoid(x: f32) -> f32 {
+    1.0 / (1.0 + (-x.clamp(-10.0, 10.0)).exp())
+}
+
+fn sample_weighted<R: Rng>(weights: &[f64], rng: &mut R) -> usize {
+    let total: f64 = weights.iter().sum();
+    let mut target = rng.r#gen::<f64>() * total;
+    for (i, w) in weights.iter().enumerate() {
+        if target < *w {
+            return i;
+        }
+        target -= w;
+    }
+    weights.len() - 1
+}
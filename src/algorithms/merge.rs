@@ -0,0 +1,611 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use petgraph::EdgeType;
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::energy::edge_created_at;
+use crate::models::graph_models::{EdgeData, EdgeInstance, EdgeKind, Grapho, Node};
+use crate::models::node_types::NodeTypes;
+use crate::models::props::Props;
+
+/// How to settle a field both sides changed differently since the base.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MergeStrategy {
+    Ours,
+    Theirs,
+    /// The side whose `metadata.updated_at` is later (ours on ties). A deleted
+    /// node loses against a modified one.
+    Newest,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MergeSide {
+    Ours,
+    Theirs,
+}
+
+/// A field changed on both sides. Values are JSON; `None` means the element
+/// was deleted on that side. When two different nodes end up with the same
+/// `name`, `ours` and `theirs` hold the ids of the claimants, `id` is the one
+/// that lost and was renamed to `name~<id prefix>`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeConflict {
+    pub id: Uuid,
+    pub name: String,
+    pub field: String,
+    pub base: Option<String>,
+    pub ours: Option<String>,
+    pub theirs: Option<String>,
+    pub resolved_with: MergeSide,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MergeReport {
+    pub conflicts: Vec<MergeConflict>,
+}
+
+impl MergeReport {
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+
+    fn resolve(
+        &mut self,
+        strategy: MergeStrategy,
+        conflict: (Uuid, &str, &str),
+        values: (Option<String>, Option<String>, Option<String>),
+        times: (DateTime<Utc>, DateTime<Utc>),
+    ) -> MergeSide {
+        let (id, name, field) = conflict;
+        let (base, ours, theirs) = values;
+        let side = pick_side(strategy, (ours.is_some(), theirs.is_some()), times);
+        self.record(id, name, field, (base, ours, theirs), side);
+        side
+    }
+
+    fn record(
+        &mut self,
+        id: Uuid,
+        name: &str,
+        field: &str,
+        values: (Option<String>, Option<String>, Option<String>),
+        side: MergeSide,
+    ) {
+        let (base, ours, theirs) = values;
+        self.conflicts.push(MergeConflict {
+            id,
+            name: name.to_string(),
+            field: field.to_string(),
+            base,
+            ours,
+            theirs,
+            resolved_with: side,
+        });
+    }
+}
+
+fn pick_side(
+    strategy: MergeStrategy,
+    present: (bool, bool),
+    times: (DateTime<Utc>, DateTime<Utc>),
+) -> MergeSide {
+    match strategy {
+        MergeStrategy::Ours => MergeSide::Ours,
+        MergeStrategy::Theirs => MergeSide::Theirs,
+        MergeStrategy::Newest if !present.0 => MergeSide::Theirs,
+        MergeStrategy::Newest if !present.1 => MergeSide::Ours,
+        MergeStrategy::Newest if times.1 > times.0 => MergeSide::Theirs,
+        MergeStrategy::Newest => MergeSide::Ours,
+    }
+}
+
+enum Pick {
+    Ours,
+    Theirs,
+    Conflict,
+}
+
+fn three_way(base: Option<&str>, ours: &str, theirs: &str) -> Pick {
+    if ours == theirs || base == Some(theirs) {
+        Pick::Ours
+    } else if base == Some(ours) {
+        Pick::Theirs
+    } else {
+        Pick::Conflict
+    }
+}
+
+fn render<S: Serialize + ?Sized>(value: &S) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+fn node_fields<T: Serialize>(node: &Node<T>) -> [(&'static str, String); 4] {
+    [
+        ("name", render(&node.name)),
+        ("content", render(&node.content)),
+        ("description", render(&node.metadata.description)),
+        ("labels", render(&node.labels)),
+    ]
+}
+
+impl<T: NodeTypes, Ty: EdgeType> Grapho<T, Ty>
+where
+    T: Clone + Serialize + for<'de> Deserialize<'de>,
+    Ty: EdgeType + Serialize + for<'de> Deserialize<'de>,
+{
+    /// Three-way merge of two copies of `base`, matching nodes, edge kinds and
+    /// edge instances by UUID. Names, contents, descriptions and labels are
+    /// merged per field, properties per key; conflicts are settled with
+    /// `strategy` and listed in the report. Fails when the merged nodes embed
+    /// vectors of different sizes.
+    pub fn merge(
+        base: &Self,
+        ours: &Self,
+        theirs: &Self,
+        strategy: MergeStrategy,
+    ) -> Result<(Self, MergeReport), String> {
+        let mut report = MergeReport::default();
+        let mut merged = ours.empty_like(&ours.name);
+        merged.metadata = ours.metadata.clone();
+
+        let base_nodes = base.nodes_by_id();
+        let ours_nodes = ours.nodes_by_id();
+        let theirs_nodes = theirs.nodes_by_id();
+        let mut ids: Vec<Uuid> = ours.core.node_weights().map(|n| n.metadata.id).collect();
+        ids.extend(
+            theirs
+                .core
+                .node_weights()
+                .map(|n| n.metadata.id)
+                .filter(|id| !ours_nodes.contains_key(id)),
+        );
+
+        let mut nodes = Vec::new();
+        for id in ids {
+            let base_node = base_nodes.get(&id).copied();
+            let node = match (ours_nodes.get(&id), theirs_nodes.get(&id)) {
+                (Some(o), Some(t)) => Some(merge_node(base_node, o, t, strategy, &mut report)),
+                (Some(only), None) | (None, Some(only)) => {
+                    let ours_side = ours_nodes.contains_key(&id);
+                    keep_one_sided(base_node, only, ours_side, strategy, &mut report)
+                }
+                (None, None) => None,
+            };
+            nodes.extend(node);
+        }
+        resolve_name_collisions(&mut nodes, &base_nodes, &ours_nodes, strategy, &mut report);
+        let mut index_by_id: HashMap<Uuid, NodeIndex> = HashMap::new();
+        for node in &nodes {
+            let idx = merged
+                .insert_node(node)
+                .map_err(|e| format!("Error to merge graphs: {}", e))?;
+            index_by_id.insert(node.metadata.id, idx);
+        }
+
+        let kinds = merge_edge_kinds(base, ours, theirs, strategy, &mut report);
+        let base_edges = base.edge_instances();
+        let ours_edges = ours.edge_instances();
+        let theirs_edges = theirs.edge_instances();
+        let mut seen = HashSet::new();
+        for (uuid, edge, in_other) in
+            ours.edge_order()
+                .into_iter()
+                .filter_map(|u| Some((u, ours_edges.get(&u)?, theirs_edges.contains_key(&u))))
+                .chain(
+                    theirs.edge_order().into_iter().filter_map(|u| {
+                        Some((u, theirs_edges.get(&u)?, ours_edges.contains_key(&u)))
+                    }),
+                )
+        {
+            if !seen.insert(uuid) {
+                continue;
+            }
+            let (Some(&from), Some(&to)) = (index_by_id.get(&edge.from), index_by_id.get(&edge.to))
+            else {
+                continue;
+            };
+            // one-sided edges that existed in the base were deleted by the other side
+            if let Some(base_edge) = base_edges.get(&uuid).filter(|_| !in_other) {
+                let ours_side = ours_edges.contains_key(&uuid);
+                if !keep_one_sided_edge(uuid, base_edge, edge, ours_side, strategy, &mut report) {
+                    continue;
+                }
+            }
+            let data = match (ours_edges.get(&uuid), theirs_edges.get(&uuid)) {
+                (Some(o), Some(t)) => {
+                    let base_data = base_edges.get(&uuid).and_then(|b| b.data);
+                    merge_edge_data(
+                        base_data,
+                        (o.data, t.data),
+                        &o.kind.name,
+                        strategy,
+                        &mut report,
+                    )
+                }
+                _ => edge.data.cloned(),
+            };
+            let edge = EdgeInstance {
+                kind: kinds.get(&edge.kind.metadata.id).unwrap_or(edge.kind),
+                data: data.as_ref(),
+                ..*edge
+            };
+            merged.insert_edge_instance(from, to, uuid, &edge);
+        }
+
+        merged.recompute_energy();
+        merged.rebuild_indexes();
+        Ok((merged, report))
+    }
+
+    fn nodes_by_id(&self) -> HashMap<Uuid, &Node<T>> {
+        self.core
+            .node_weights()
+            .map(|n| (n.metadata.id, n))
+            .collect()
+    }
+
+    pub(crate) fn edge_order(&self) -> Vec<Uuid> {
+        let mut refs: Vec<_> = self.runtime_ref.edges.iter().collect();
+        refs.sort_by_key(|r| r.index);
+        refs.into_iter().map(|r| r.uuid).collect()
+    }
+
+    pub(crate) fn edge_instances(&self) -> HashMap<Uuid, EdgeInstance<'_>> {
+        let by_index: HashMap<_, _> = self
+            .runtime_ref
+            .edges
+            .iter()
+            .map(|r| (r.index, r))
+            .collect();
+        self.core
+            .edge_references()
+            .filter_map(|e| {
+                let r = by_index.get(&e.id())?;
+                let kind = self.edge_kinds.iter().find(|k| k.name == r.name)?;
+                let created_at = edge_created_at(self, r);
+                Some((
+                    r.uuid,
+                    EdgeInstance {
+                        from: self.core[e.source()].metadata.id,
+                        to: self.core[e.target()].metadata.id,
+                        weight: *e.weight(),
+                        kind,
+                        created_at,
+                        data: self.edge_data.get(&r.uuid),
+                    },
+                ))
+            })
+            .collect()
+    }
+}
+
+// Nodes are matched by id but the graph is keyed by name: when two different
+// nodes end up with the same name (both sides added one, or a rename landed on
+// a name the other side uses), the losing claimant is renamed and reported.
+fn resolve_name_collisions<T>(
+    nodes: &mut [Node<T>],
+    base: &HashMap<Uuid, &Node<T>>,
+    ours: &HashMap<Uuid, &Node<T>>,
+    strategy: MergeStrategy,
+    report: &mut MergeReport,
+) {
+    let mut holders: HashMap<String, usize> = HashMap::new();
+    for i in 0..nodes.len() {
+        let Some(&j) = holders.get(&nodes[i].name) else {
+            holders.insert(nodes[i].name.clone(), i);
+            continue;
+        };
+        // a node claims the name through ours when ours already calls it that
+        let via_ours = |n: &Node<T>| ours.get(&n.metadata.id).is_some_and(|o| o.name == n.name);
+        let (o, t) = if via_ours(&nodes[i]) { (i, j) } else { (j, i) };
+        let side = pick_side(
+            strategy,
+            (true, true),
+            (nodes[o].metadata.updated_at, nodes[t].metadata.updated_at),
+        );
+        let (winner, loser) = match side {
+            MergeSide::Ours => (o, t),
+            MergeSide::Theirs => (t, o),
+        };
+        let name = nodes[loser].name.clone();
+        let id = nodes[loser].metadata.id;
+        report.record(
+            id,
+            &name,
+            "name",
+            (
+                base.get(&id).map(|b| render(&b.name)),
+                Some(render(&nodes[o].metadata.id)),
+                Some(render(&nodes[t].metadata.id)),
+            ),
+            side,
+        );
+        nodes[loser].name = format!("{}~{}", name, &id.simple().to_string()[..8]);
+        holders.insert(name, winner);
+        holders.insert(nodes[loser].name.clone(), loser);
+    }
+}
+
+fn merge_node<T: Clone + Serialize>(
+    base: Option<&Node<T>>,
+    ours: &Node<T>,
+    theirs: &Node<T>,
+    strategy: MergeStrategy,
+    report: &mut MergeReport,
+) -> Node<T> {
+    let mut node = ours.clone();
+    let base_fields = base.map(node_fields);
+    let (ours_fields, theirs_fields) = (node_fields(ours), node_fields(theirs));
+    for i in 0..ours_fields.len() {
+        let field = ours_fields[i].0;
+        let base_value = base_fields.as_ref().map(|f| f[i].1.as_str());
+        let take_theirs = match three_way(base_value, &ours_fields[i].1, &theirs_fields[i].1) {
+            Pick::Ours => false,
+            Pick::Theirs => true,
+            Pick::Conflict => {
+                let side = report.resolve(
+                    strategy,
+                    (ours.metadata.id, &ours.name, field),
+                    (
+                        base_value.map(str::to_string),
+                        Some(ours_fields[i].1.clone()),
+                        Some(theirs_fields[i].1.clone()),
+                    ),
+                    (ours.metadata.updated_at, theirs.metadata.updated_at),
+                );
+                side == MergeSide::Theirs
+            }
+        };
+        if take_theirs {
+            match field {
+                "name" => node.name = theirs.name.clone(),
+                "content" => node.content = theirs.content.clone(),
+                "labels" => node.labels = theirs.labels.clone(),
+                _ => node.metadata.description = theirs.metadata.description.clone(),
+            }
+        }
+    }
+    node.props = merge_props(
+        base.map(|b| &b.props),
+        (&ours.props, ours.metadata.updated_at),
+        (&theirs.props, theirs.metadata.updated_at),
+        (ours.metadata.id, &ours.name),
+        strategy,
+        report,
+    );
+    node.metadata.updated_at = ours.metadata.updated_at.max(theirs.metadata.updated_at);
+    node
+}
+
+// Properties merge key by key; a key missing on one side counts as deleted there.
+fn merge_props(
+    base: Option<&Props>,
+    ours: (&Props, DateTime<Utc>),
+    theirs: (&Props, DateTime<Utc>),
+    owner: (Uuid, &str),
+    strategy: MergeStrategy,
+    report: &mut MergeReport,
+) -> Props {
+    let mut merged = ours.0.clone();
+    let keys: BTreeSet<&String> = base
+        .into_iter()
+        .flat_map(|b| b.keys())
+        .chain(ours.0.keys())
+        .chain(theirs.0.keys())
+        .collect();
+    for key in keys {
+        let base_value = base.map(|b| render(&b.get(key)));
+        let (o, t) = (ours.0.get(key), theirs.0.get(key));
+        let take_theirs = match three_way(base_value.as_deref(), &render(&o), &render(&t)) {
+            Pick::Ours => false,
+            Pick::Theirs => true,
+            Pick::Conflict => {
+                let side = report.resolve(
+                    strategy,
+                    (owner.0, owner.1, &format!("props.{}", key)),
+                    (
+                        base.and_then(|b| b.get(key)).map(render),
+                        o.map(render),
+                        t.map(render),
+                    ),
+                    (ours.1, theirs.1),
+                );
+                side == MergeSide::Theirs
+            }
+        };
+        if take_theirs {
+            match t {
+                Some(value) => merged.insert(key.clone(), value.clone()),
+                None => merged.remove(key),
+            };
+        }
+    }
+    merged
+}
+
+// Instance data of an edge kept by both sides: description and properties.
+fn merge_edge_data(
+    base: Option<&EdgeData>,
+    (ours, theirs): (Option<&EdgeData>, Option<&EdgeData>),
+    kind_name: &str,
+    strategy: MergeStrategy,
+    report: &mut MergeReport,
+) -> Option<EdgeData> {
+    let (o, t) = match (ours, theirs) {
+        (Some(o), Some(t)) => (o, t),
+        (one, other) => return one.or(other).cloned(),
+    };
+    let mut data = o.clone();
+    let id = o.metadata.id;
+    let times = (o.metadata.updated_at, t.metadata.updated_at);
+    let base_description = base.map(|b| b.metadata.description.as_str());
+    match three_way(
+        base_description,
+        &o.metadata.description,
+        &t.metadata.description,
+    ) {
+        Pick::Ours => {}
+        Pick::Theirs => data.metadata.description = t.metadata.description.clone(),
+        Pick::Conflict => {
+            let side = report.resolve(
+                strategy,
+                (id, kind_name, "description"),
+                (
+                    base_description.map(str::to_string),
+                    Some(o.metadata.description.clone()),
+                    Some(t.metadata.description.clone()),
+                ),
+                times,
+            );
+            if side == MergeSide::Theirs {
+                data.metadata.description = t.metadata.description.clone();
+            }
+        }
+    }
+    data.props = merge_props(
+        base.map(|b| &b.props),
+        (&o.props, times.0),
+        (&t.props, times.1),
+        (id, kind_name),
+        strategy,
+        report,
+    );
+    data.metadata.updated_at = times.0.max(times.1);
+    Some(data)
+}
+
+// A node present on one side only: new, deleted by the other side, or both.
+fn keep_one_sided<T: Clone + Serialize>(
+    base: Option<&Node<T>>,
+    node: &Node<T>,
+    ours_side: bool,
+    strategy: MergeStrategy,
+    report: &mut MergeReport,
+) -> Option<Node<T>> {
+    let Some(base) = base else {
+        return Some(node.clone());
+    };
+    if node_fields(base) == node_fields(node) && base.props == node.props {
+        return None;
+    }
+    let rendered = Some(render(&node.content));
+    let (ours, theirs) = if ours_side {
+        (rendered, None)
+    } else {
+        (None, rendered)
+    };
+    let side = report.resolve(
+        strategy,
+        (node.metadata.id, &node.name, "node"),
+        (Some(render(&base.content)), ours, theirs),
+        (node.metadata.updated_at, node.metadata.updated_at),
+    );
+    ((side == MergeSide::Ours) == ours_side).then(|| node.clone())
+}
+
+fn edge_fields(edge: &EdgeInstance<'_>) -> String {
+    let data = edge.data.map(|d| (&d.metadata.description, &d.props));
+    render(&(edge.weight, data))
+}
+
+// An edge instance that one side deleted: dropped unless the other side
+// changed it since the base, which is a conflict.
+fn keep_one_sided_edge(
+    uuid: Uuid,
+    base: &EdgeInstance<'_>,
+    edge: &EdgeInstance<'_>,
+    ours_side: bool,
+    strategy: MergeStrategy,
+    report: &mut MergeReport,
+) -> bool {
+    let (before, after) = (edge_fields(base), edge_fields(edge));
+    if before == after {
+        return false;
+    }
+    let rendered = Some(after);
+    let (ours, theirs) = if ours_side {
+        (rendered, None)
+    } else {
+        (None, rendered)
+    };
+    let updated_at = edge
+        .data
+        .map(|d| d.metadata.updated_at)
+        .or(edge.created_at)
+        .unwrap_or_default();
+    let side = report.resolve(
+        strategy,
+        (uuid, &edge.kind.name, "edge"),
+        (Some(before), ours, theirs),
+        (updated_at, updated_at),
+    );
+    (side == MergeSide::Ours) == ours_side
+}
+
+fn merge_edge_kinds<T: NodeTypes, Ty: EdgeType>(
+    base: &Grapho<T, Ty>,
+    ours: &Grapho<T, Ty>,
+    theirs: &Grapho<T, Ty>,
+    strategy: MergeStrategy,
+    report: &mut MergeReport,
+) -> HashMap<Uuid, EdgeKind> {
+    let find =
+        |g: &Grapho<T, Ty>, id: Uuid| g.edge_kinds.iter().find(|k| k.metadata.id == id).cloned();
+    let mut merged = HashMap::new();
+    for kind in ours.edge_kinds.iter().chain(&theirs.edge_kinds) {
+        let id = kind.metadata.id;
+        if merged.contains_key(&id) {
+            continue;
+        }
+        let mut result = kind.clone();
+        if let (Some(o), Some(t)) = (find(ours, id), find(theirs, id)) {
+            let base_description = find(base, id).map(|k| k.metadata.description);
+            match three_way(
+                base_description.as_deref(),
+                &o.metadata.description,
+                &t.metadata.description,
+            ) {
+                Pick::Ours => result = o,
+                Pick::Theirs => result = t,
+                Pick::Conflict => {
+                    let side = report.resolve(
+                        strategy,
+                        (id, &o.name, "description"),
+                        (
+                            base_description,
+                            Some(o.metadata.description.clone()),
+                            Some(t.metadata.description.clone()),
+                        ),
+                        (o.metadata.updated_at, t.metadata.updated_at),
+                    );
+                    result = if side == MergeSide::Ours { o } else { t };
+                }
+            }
+        }
+        merged.insert(id, result);
+    }
+    merged
+}
+
+impl fmt::Display for MergeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_clean() {
+            return writeln!(f, "Merged without conflicts.");
+        }
+        writeln!(f, "{} conflict(s):", self.conflicts.len())?;
+        let show = |v: &Option<String>| v.clone().unwrap_or_else(|| "<deleted>".to_string());
+        for c in &self.conflicts {
+            writeln!(f, "! {} ({}) {}", c.name, c.id, c.field)?;
+            writeln!(f, "    base:   {}", show(&c.base))?;
+            writeln!(f, "    ours:   {}", show(&c.ours))?;
+            writeln!(f, "    theirs: {}", show(&c.theirs))?;
+            writeln!(f, "    kept:   {:?}", c.resolved_with)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,12 @@
+pub mod centrality;
+pub mod community;
+pub mod components;
+pub mod cycles;
+pub mod diff;
+pub mod embedding;
+pub mod merge;
+pub mod set_ops;
+pub mod similarity;
+pub mod spanning;
+pub mod stats;
+pub mod subgraph;
@@ -0,0 +1,237 @@
+use std::collections::{HashMap, HashSet};
+
+use petgraph::EdgeType;
+use petgraph::graph::NodeIndex;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::graph_models::{ConnectionError, EdgeInstance, Grapho, Node};
+use crate::models::node_types::NodeTypes;
+
+/// What makes a node in one graph "the same" as a node in another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum NodeIdentity {
+    #[default]
+    Name,
+    Uuid,
+}
+
+impl NodeIdentity {
+    fn key<T>(&self, node: &Node<T>) -> String {
+        match self {
+            NodeIdentity::Name => node.name.clone(),
+            NodeIdentity::Uuid => node.metadata.id.to_string(),
+        }
+    }
+}
+
+// Matching by name compares (from, to, edge kind) as a multiset, endpoints
+// sorted for undirected graphs; matching by uuid compares edge instances.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum EdgeKey {
+    Endpoints(String, String, String),
+    Instance(Uuid),
+}
+
+struct KeyedEdge<'a> {
+    key: EdgeKey,
+    uuid: Uuid,
+    from: String,
+    to: String,
+    edge: EdgeInstance<'a>,
+}
+
+impl<T: NodeTypes, Ty: EdgeType> Grapho<T, Ty>
+where
+    T: Clone + Serialize + for<'de> Deserialize<'de>,
+    Ty: EdgeType + Serialize + for<'de> Deserialize<'de>,
+{
+    /// Every node and connection of both graphs; shared ones are taken from `self`.
+    /// Matching by `Name`, parallel connections count as a multiset, so the larger
+    /// count wins; matching by `Uuid`, connections are shared when their edge
+    /// instance ids are. Fails
+    /// when matching by `Uuid` and two different nodes share a name, since the
+    /// result could not keep them apart, or when the graphs embed nodes in
+    /// vectors of different sizes.
+    pub fn union(&self, other: &Self, identity: NodeIdentity) -> Result<Self, String> {
+        if identity == NodeIdentity::Uuid {
+            self.check_name_collisions(other)?;
+        }
+        let mut g = self.empty_like(&format!("{}-union-{}", self.name, other.name));
+        let mut nodes = HashMap::new();
+        g.copy_nodes(self, identity, &mut nodes, |_| true)
+            .map_err(|e| format!("Error to union graphs: {}", e))?;
+        g.copy_nodes(other, identity, &mut nodes, |_| true)
+            .map_err(|e| format!("Error to union graphs: {}", e))?;
+        let mut budget = self.edge_counts(identity);
+        g.copy_edges(self, identity, &nodes, |_| true);
+        g.copy_edges(other, identity, &nodes, |key| !take(&mut budget, key));
+        Ok(g.finish_set_operation())
+    }
+
+    /// Nodes present in both graphs and the connections both graphs share between
+    /// them. Nodes come from `self` only, so names never collide.
+    pub fn intersection(&self, other: &Self, identity: NodeIdentity) -> Result<Self, String> {
+        let mut g = self.empty_like(&format!("{}-intersection-{}", self.name, other.name));
+        let other_nodes = other.node_keys(identity);
+        let mut budget = other.edge_counts(identity);
+        let mut nodes = HashMap::new();
+        g.copy_nodes(self, identity, &mut nodes, |key| other_nodes.contains(key))
+            .map_err(|e| format!("Error to intersect graphs: {}", e))?;
+        g.copy_edges(self, identity, &nodes, |key| take(&mut budget, key));
+        Ok(g.finish_set_operation())
+    }
+
+    /// Connections of `self` missing from `other` (with their endpoints), plus
+    /// the nodes of `self` that `other` does not have.
+    pub fn difference(&self, other: &Self, identity: NodeIdentity) -> Result<Self, String> {
+        let mut g = self.empty_like(&format!("{}-difference-{}", self.name, other.name));
+        let other_nodes = other.node_keys(identity);
+        let mut budget = other.edge_counts(identity);
+        let mut endpoints: HashSet<String> = HashSet::new();
+        let mut kept: HashSet<Uuid> = HashSet::new();
+        for keyed in self.keyed_edges(identity) {
+            if !take(&mut budget, &keyed.key) {
+                endpoints.insert(keyed.from);
+                endpoints.insert(keyed.to);
+                kept.insert(keyed.uuid);
+            }
+        }
+        let mut nodes = HashMap::new();
+        g.copy_nodes(self, identity, &mut nodes, |key| {
+            !other_nodes.contains(key) || endpoints.contains(key)
+        })
+        .map_err(|e| format!("Error to diff graphs: {}", e))?;
+        g.copy_edges_where(self, identity, &nodes, |keyed| kept.contains(&keyed.uuid));
+        Ok(g.finish_set_operation())
+    }
+
+    // Nodes of `other` that `self` lacks are added by name, which must be free.
+    fn check_name_collisions(&self, other: &Self) -> Result<(), String> {
+        let ids: HashSet<Uuid> = self.core.node_weights().map(|n| n.metadata.id).collect();
+        for node in other.core.node_weights() {
+            if ids.contains(&node.metadata.id) {
+                continue;
+            }
+            if let Some(idx) = self.get_node_index_by_name(&node.name) {
+                return Err(format!(
+                    "Error to union graphs: name {} is used by different nodes ({} and {})",
+                    node.name, self.core[idx].metadata.id, node.metadata.id
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn finish_set_operation(mut self) -> Self {
+        self.recompute_energy();
+        self.rebuild_indexes();
+        self
+    }
+
+    fn node_keys(&self, identity: NodeIdentity) -> HashSet<String> {
+        self.core.node_weights().map(|n| identity.key(n)).collect()
+    }
+
+    fn edge_counts(&self, identity: NodeIdentity) -> HashMap<EdgeKey, usize> {
+        let mut counts = HashMap::new();
+        for keyed in self.keyed_edges(identity) {
+            *counts.entry(keyed.key).or_default() += 1;
+        }
+        counts
+    }
+
+    // Edge instances in insertion order, keyed for comparison across graphs.
+    fn keyed_edges(&self, identity: NodeIdentity) -> Vec<KeyedEdge<'_>> {
+        let keys: HashMap<Uuid, String> = self
+            .core
+            .node_weights()
+            .map(|n| (n.metadata.id, identity.key(n)))
+            .collect();
+        let mut instances = self.edge_instances();
+        self.edge_order()
+            .into_iter()
+            .filter_map(|uuid| {
+                let edge = instances.remove(&uuid)?;
+                let (from, to) = (keys[&edge.from].clone(), keys[&edge.to].clone());
+                let kind = edge.kind.name.clone();
+                let key = match identity {
+                    NodeIdentity::Uuid => EdgeKey::Instance(uuid),
+                    NodeIdentity::Name if !self.core.is_directed() && from > to => {
+                        EdgeKey::Endpoints(to.clone(), from.clone(), kind)
+                    }
+                    NodeIdentity::Name => EdgeKey::Endpoints(from.clone(), to.clone(), kind),
+                };
+                Some(KeyedEdge {
+                    key,
+                    uuid,
+                    from,
+                    to,
+                    edge,
+                })
+            })
+            .collect()
+    }
+
+    fn copy_nodes<F>(
+        &mut self,
+        source: &Self,
+        identity: NodeIdentity,
+        nodes: &mut HashMap<String, NodeIndex>,
+        keep: F,
+    ) -> Result<(), ConnectionError>
+    where
+        F: Fn(&String) -> bool,
+    {
+        for node in source.core.node_weights() {
+            let key = identity.key(node);
+            if !nodes.contains_key(&key) && keep(&key) {
+                let idx = self.insert_node(node)?;
+                nodes.insert(key, idx);
+            }
+        }
+        Ok(())
+    }
+
+    fn copy_edges<F>(
+        &mut self,
+        source: &Self,
+        identity: NodeIdentity,
+        nodes: &HashMap<String, NodeIndex>,
+        mut keep: F,
+    ) where
+        F: FnMut(&EdgeKey) -> bool,
+    {
+        self.copy_edges_where(source, identity, nodes, |keyed| keep(&keyed.key));
+    }
+
+    fn copy_edges_where<F>(
+        &mut self,
+        source: &Self,
+        identity: NodeIdentity,
+        nodes: &HashMap<String, NodeIndex>,
+        mut keep: F,
+    ) where
+        F: FnMut(&KeyedEdge<'_>) -> bool,
+    {
+        for keyed in source.keyed_edges(identity) {
+            let (Some(&from), Some(&to)) = (nodes.get(&keyed.from), nodes.get(&keyed.to)) else {
+                continue;
+            };
+            if keep(&keyed) {
+                self.insert_edge_instance(from, to, keyed.uuid, &keyed.edge);
+            }
+        }
+    }
+}
+
+// Consumes one occurrence of `key`; false once none are left.
+fn take(budget: &mut HashMap<EdgeKey, usize>, key: &EdgeKey) -> bool {
+    match budget.get_mut(key) {
+        Some(n) if *n > 0 => {
+            *n -= 1;
+            true
+        }
+        _ => false,
+    }
+}
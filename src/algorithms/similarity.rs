@@ -0,0 +1,115 @@
+use std::collections::{HashMap, HashSet};
+
+use petgraph::EdgeType;
+use petgraph::graph::NodeIndex;
+use serde::{Deserialize, Serialize};
+
+use crate::models::graph_models::{Grapho, Ref};
+use crate::models::node_types::NodeTypes;
+
+/// Neighbourhood-based score between two nodes, computed from
+/// `outgoing` connections, so directed graphs score successors only.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkScore {
+    CommonNeighbors,
+    Jaccard,
+    AdamicAdar,
+    PreferentialAttachment,
+}
+
+impl<T: NodeTypes, Ty: EdgeType> Grapho<T, Ty>
+where
+    T: Clone + Serialize + for<'de> Deserialize<'de>,
+    Ty: EdgeType + Serialize + for<'de> Deserialize<'de>,
+{
+    pub fn link_score(&self, a: NodeIndex, b: NodeIndex, score: LinkScore) -> f64 {
+        let (na, nb) = (self.neighbor_set(a, None), self.neighbor_set(b, None));
+        match score {
+            LinkScore::CommonNeighbors => na.intersection(&nb).count() as f64,
+            LinkScore::Jaccard => {
+                let union = na.union(&nb).count();
+                if union == 0 {
+                    0.0
+                } else {
+                    na.intersection(&nb).count() as f64 / union as f64
+                }
+            }
+            LinkScore::AdamicAdar => self.adamic_adar_over(&na, &nb, None),
+            LinkScore::PreferentialAttachment => (na.len() * nb.len()) as f64,
+        }
+    }
+
+    pub fn common_neighbors(&self, a: NodeIndex, b: NodeIndex) -> Vec<Ref<NodeIndex>> {
+        let nb = self.neighbor_set(b, None);
+        let mut common: Vec<NodeIndex> = self
+            .neighbor_set(a, None)
+            .into_iter()
+            .filter(|idx| nb.contains(idx))
+            .collect();
+        common.sort();
+        common.into_iter().map(|idx| self.node_ref(idx)).collect()
+    }
+
+    pub fn jaccard(&self, a: NodeIndex, b: NodeIndex) -> f64 {
+        self.link_score(a, b, LinkScore::Jaccard)
+    }
+
+    pub fn adamic_adar(&self, a: NodeIndex, b: NodeIndex) -> f64 {
+        self.link_score(a, b, LinkScore::AdamicAdar)
+    }
+
+    pub fn preferential_attachment(&self, a: NodeIndex, b: NodeIndex) -> f64 {
+        self.link_score(a, b, LinkScore::PreferentialAttachment)
+    }
+
+    /// Ranks nodes two `edge_kind` hops away from `node` that are not yet linked
+    /// to it by that kind, scored with Adamic-Adar over `edge_kind` neighbours.
+    pub fn suggest_connections(
+        &self,
+        node: NodeIndex,
+        edge_kind: &str,
+        k: usize,
+    ) -> Vec<(Ref<NodeIndex>, f64)> {
+        let direct = self.neighbor_set(node, Some(edge_kind));
+        let mut candidates: HashMap<NodeIndex, f64> = HashMap::new();
+        for &via in &direct {
+            for candidate in self.neighbor_set(via, Some(edge_kind)) {
+                if candidate == node || direct.contains(&candidate) {
+                    continue;
+                }
+                candidates.entry(candidate).or_insert_with(|| {
+                    let other = self.neighbor_set(candidate, Some(edge_kind));
+                    self.adamic_adar_over(&direct, &other, Some(edge_kind))
+                });
+            }
+        }
+        let mut ranked: Vec<_> = candidates
+            .into_iter()
+            .map(|(idx, score)| (self.node_ref(idx), score))
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.name.cmp(&b.0.name)));
+        ranked.truncate(k);
+        ranked
+    }
+
+    fn neighbor_set(&self, node: NodeIndex, edge_kind: Option<&str>) -> HashSet<NodeIndex> {
+        self.outgoing(node)
+            .into_iter()
+            .filter(|c| edge_kind.is_none_or(|kind| c.edge.name == kind))
+            .map(|c| c.node.index)
+            .collect()
+    }
+
+    fn adamic_adar_over(
+        &self,
+        a: &HashSet<NodeIndex>,
+        b: &HashSet<NodeIndex>,
+        edge_kind: Option<&str>,
+    ) -> f64 {
+        a.intersection(b)
+            .map(|&z| self.neighbor_set(z, edge_kind).len())
+            .filter(|&degree| degree > 1)
+            .map(|degree| 1.0 / (degree as f64).ln())
+            .sum()
+    }
+}
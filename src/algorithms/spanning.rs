@@ -0,0 +1,33 @@
+use std::collections::HashSet;
+
+use petgraph::EdgeType;
+use petgraph::graph::{EdgeIndex, NodeIndex};
+use petgraph::unionfind::UnionFind;
+use petgraph::visit::EdgeRef;
+use serde::{Deserialize, Serialize};
+
+use crate::models::graph_models::Grapho;
+use crate::models::node_types::NodeTypes;
+
+impl<T: NodeTypes, Ty: EdgeType> Grapho<T, Ty>
+where
+    T: Clone + Serialize + for<'de> Deserialize<'de>,
+    Ty: EdgeType + Serialize + for<'de> Deserialize<'de>,
+{
+    /// Kruskal over the edge weights, ignoring direction. Every node is kept and
+    /// only the connections backed by a forest edge survive.
+    pub fn minimum_spanning_forest(&self) -> Self {
+        let mut edges: Vec<_> = self.core.edge_references().collect();
+        edges.sort_by_key(|e| (*e.weight(), e.id()));
+        let mut sets = UnionFind::<usize>::new(self.core.node_count());
+        let forest: HashSet<EdgeIndex> = edges
+            .into_iter()
+            .filter(|e| sets.union(e.source().index(), e.target().index()))
+            .map(|e| e.id())
+            .collect();
+        let nodes: HashSet<NodeIndex> = self.core.node_indices().collect();
+        self.extract(&format!("{}-msf", self.name), &nodes, |e| {
+            forest.contains(&e)
+        })
+    }
+}
@@ -0,0 +1,215 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+use petgraph::EdgeType;
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use serde::{Deserialize, Serialize};
+
+use crate::models::graph_models::Grapho;
+use crate::models::node_types::NodeTypes;
+
+/// Health report over `core`, treated as a simple undirected graph.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphStats {
+    pub name: String,
+    pub node_count: usize,
+    pub edge_count: usize,
+    pub triangles: usize,
+    pub global_clustering: f64,
+    pub average_clustering: f64,
+    pub degeneracy: usize,
+    pub content_size: usize, // sum of NodeTypes::size_estimate
+    pub nodes: Vec<NodeStats>,
+    pub edge_kinds: Vec<(String, f64)>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeStats {
+    pub name: String,
+    pub degree: usize,
+    pub triangles: usize,
+    pub clustering: f64,
+    pub core_number: usize,
+}
+
+impl<T: NodeTypes, Ty: EdgeType> Grapho<T, Ty>
+where
+    T: Clone + Serialize + for<'de> Deserialize<'de>,
+    Ty: EdgeType + Serialize + for<'de> Deserialize<'de>,
+{
+    /// Triangles each node takes part in.
+    pub fn triangle_counts(&self) -> HashMap<NodeIndex, usize> {
+        let counts = triangles(&self.simple_adjacency());
+        self.core.node_indices().zip(counts).collect()
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        triangles(&self.simple_adjacency()).iter().sum::<usize>() / 3
+    }
+
+    /// Local clustering coefficient; 0 for nodes with fewer than two neighbours.
+    pub fn clustering_coefficients(&self) -> HashMap<NodeIndex, f64> {
+        let adjacency = self.simple_adjacency();
+        let counts = triangles(&adjacency);
+        self.core
+            .node_indices()
+            .map(|idx| {
+                let i = idx.index();
+                (idx, local_clustering(adjacency[i].len(), counts[i]))
+            })
+            .collect()
+    }
+
+    pub fn average_clustering(&self) -> f64 {
+        let n = self.core.node_count();
+        if n == 0 {
+            return 0.0;
+        }
+        self.clustering_coefficients().values().sum::<f64>() / n as f64
+    }
+
+    /// Transitivity: closed triplets over all connected triplets.
+    pub fn global_clustering(&self) -> f64 {
+        let adjacency = self.simple_adjacency();
+        let closed: usize = triangles(&adjacency).iter().sum();
+        let triplets: usize = adjacency
+            .iter()
+            .map(|n| n.len() * n.len().saturating_sub(1) / 2)
+            .sum();
+        if triplets == 0 {
+            0.0
+        } else {
+            closed as f64 / triplets as f64
+        }
+    }
+
+    /// k-core number of every node (largest k such that the node is in the k-core).
+    pub fn core_numbers(&self) -> HashMap<NodeIndex, usize> {
+        let numbers = core_numbers(&self.simple_adjacency());
+        self.core.node_indices().zip(numbers).collect()
+    }
+
+    pub fn stats(&self) -> GraphStats {
+        let adjacency = self.simple_adjacency();
+        let counts = triangles(&adjacency);
+        let cores = core_numbers(&adjacency);
+        let nodes: Vec<NodeStats> = self
+            .core
+            .node_indices()
+            .map(|idx| {
+                let i = idx.index();
+                NodeStats {
+                    name: self.core[idx].name.clone(),
+                    degree: adjacency[i].len(),
+                    triangles: counts[i],
+                    clustering: local_clustering(adjacency[i].len(), counts[i]),
+                    core_number: cores[i],
+                }
+            })
+            .collect();
+        GraphStats {
+            name: self.name.clone(),
+            node_count: self.core.node_count(),
+            edge_count: self.core.edge_count(),
+            triangles: counts.iter().sum::<usize>() / 3,
+            global_clustering: self.global_clustering(),
+            average_clustering: self.average_clustering(),
+            degeneracy: cores.iter().copied().max().unwrap_or(0),
+            content_size: self
+                .core
+                .node_weights()
+                .map(|n| n.content.size_estimate())
+                .sum(),
+            nodes,
+            edge_kinds: self.edge_kind_stats(),
+        }
+    }
+
+    // Neighbour sets ignoring direction, parallel edges and self-loops.
+    fn simple_adjacency(&self) -> Vec<BTreeSet<usize>> {
+        let mut adjacency = vec![BTreeSet::new(); self.core.node_count()];
+        for e in self.core.edge_references() {
+            let (a, b) = (e.source().index(), e.target().index());
+            if a != b {
+                adjacency[a].insert(b);
+                adjacency[b].insert(a);
+            }
+        }
+        adjacency
+    }
+}
+
+fn triangles(adjacency: &[BTreeSet<usize>]) -> Vec<usize> {
+    let mut counts = vec![0; adjacency.len()];
+    for (u, neighbors) in adjacency.iter().enumerate() {
+        for &v in neighbors.range(u + 1..) {
+            for &w in adjacency[v].range(v + 1..) {
+                if neighbors.contains(&w) {
+                    counts[u] += 1;
+                    counts[v] += 1;
+                    counts[w] += 1;
+                }
+            }
+        }
+    }
+    counts
+}
+
+fn local_clustering(degree: usize, triangles: usize) -> f64 {
+    if degree < 2 {
+        return 0.0;
+    }
+    2.0 * triangles as f64 / (degree * (degree - 1)) as f64
+}
+
+// Batagelj-Zaversnik peeling: repeatedly remove a node of minimum remaining degree.
+fn core_numbers(adjacency: &[BTreeSet<usize>]) -> Vec<usize> {
+    let n = adjacency.len();
+    let mut degree: Vec<usize> = adjacency.iter().map(BTreeSet::len).collect();
+    let mut queue: BTreeSet<(usize, usize)> = (0..n).map(|i| (degree[i], i)).collect();
+    let mut cores = vec![0; n];
+    let mut k = 0;
+    while let Some((d, v)) = queue.pop_first() {
+        k = k.max(d);
+        cores[v] = k;
+        for &w in &adjacency[v] {
+            if queue.remove(&(degree[w], w)) {
+                degree[w] -= 1;
+                queue.insert((degree[w], w));
+            }
+        }
+    }
+    cores
+}
+
+impl fmt::Display for GraphStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Graph: {}", self.name)?;
+        writeln!(f, "  nodes: {}", self.node_count)?;
+        writeln!(f, "  edges: {}", self.edge_count)?;
+        writeln!(f, "  triangles: {}", self.triangles)?;
+        writeln!(f, "  global clustering: {:.4}", self.global_clustering)?;
+        writeln!(f, "  average clustering: {:.4}", self.average_clustering)?;
+        writeln!(f, "  degeneracy: {}", self.degeneracy)?;
+        writeln!(f, "  content size: {} bytes", self.content_size)?;
+        writeln!(f, "Nodes:")?;
+        writeln!(
+            f,
+            "  {:<20} {:>8} {:>10} {:>11} {:>6}",
+            "name", "degree", "triangles", "clustering", "core"
+        )?;
+        for node in &self.nodes {
+            writeln!(
+                f,
+                "  {:<20} {:>8} {:>10} {:>11.4} {:>6}",
+                node.name, node.degree, node.triangles, node.clustering, node.core_number
+            )?;
+        }
+        writeln!(f, "Edge kinds:")?;
+        for (name, energy) in &self.edge_kinds {
+            writeln!(f, "  {:<20} {:>10.2}", name, energy)?;
+        }
+        Ok(())
+    }
+}
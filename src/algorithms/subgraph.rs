@@ -0,0 +1,150 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use petgraph::EdgeType;
+use petgraph::graph::{EdgeIndex, NodeIndex};
+use petgraph::visit::EdgeRef;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::graph_models::{Connection, EdgeKindRef, Grapho, Ref};
+use crate::models::node_types::NodeTypes;
+
+impl<T: NodeTypes, Ty: EdgeType> Grapho<T, Ty>
+where
+    T: Clone + Serialize + for<'de> Deserialize<'de>,
+    Ty: EdgeType + Serialize + for<'de> Deserialize<'de>,
+{
+    /// Induced subgraph over `nodes`: every node keeps its metadata and every
+    /// core edge with both endpoints in the set is carried over.
+    pub fn subgraph<I>(&self, nodes: I) -> Self
+    where
+        I: IntoIterator<Item = NodeIndex>,
+    {
+        let nodes: HashSet<NodeIndex> = nodes.into_iter().collect();
+        self.extract(&format!("{}-subgraph", self.name), &nodes, |_| true)
+    }
+
+    /// Everything within `k` hops of `node`, following edges in both directions.
+    pub fn ego_graph(&self, node: NodeIndex, k: usize) -> Result<Self, String> {
+        self.ego(node, k, None)
+    }
+
+    /// Like `ego_graph`, but only steps onto nodes carrying `label`; the centre
+    /// is always kept.
+    pub fn ego_graph_with_label(
+        &self,
+        node: NodeIndex,
+        k: usize,
+        label: &str,
+    ) -> Result<Self, String> {
+        self.ego(node, k, Some(label))
+    }
+
+    fn ego(&self, node: NodeIndex, k: usize, label: Option<&str>) -> Result<Self, String> {
+        let center = self
+            .core
+            .node_weight(node)
+            .ok_or_else(|| format!("Node not found: {}", node.index()))?;
+        let mut seen = HashSet::from([node]);
+        let mut queue = VecDeque::from([(node, 0)]);
+        while let Some((current, depth)) = queue.pop_front() {
+            if depth == k {
+                continue;
+            }
+            for next in self.core.neighbors_undirected(current) {
+                if label.is_some_and(|l| !self.core[next].labels.contains(l)) {
+                    continue;
+                }
+                if seen.insert(next) {
+                    queue.push_back((next, depth + 1));
+                }
+            }
+        }
+        Ok(
+            self.extract(&format!("{}-ego-{}", self.name, center.name), &seen, |_| {
+                true
+            }),
+        )
+    }
+
+    /// Builds a fresh graph from the nodes in `nodes` and the core edges accepted
+    /// by `keep_edge`, rebuilding connections, runtime refs and edge kind energy.
+    pub(crate) fn extract<F>(&self, name: &str, nodes: &HashSet<NodeIndex>, keep_edge: F) -> Self
+    where
+        F: Fn(EdgeIndex) -> bool,
+    {
+        let mut g = self.empty_like(name);
+        let mut remap = HashMap::new();
+        for idx in self.core.node_indices().filter(|i| nodes.contains(i)) {
+            let mut node = self.core[idx].clone();
+            node.connections.clear();
+            let new_idx = g.core.add_node(node);
+            g.core[new_idx].node_index = new_idx;
+            remap.insert(idx, new_idx);
+        }
+
+        let edge_refs: HashMap<EdgeIndex, &Ref<EdgeIndex>> = self
+            .runtime_ref
+            .edges
+            .iter()
+            .map(|r| (r.index, r))
+            .collect();
+        let mut kept: HashSet<Uuid> = HashSet::new();
+        let mut kinds_present: HashSet<&str> = HashSet::new();
+        for e in self.core.edge_references() {
+            let (Some(&from), Some(&to)) = (remap.get(&e.source()), remap.get(&e.target())) else {
+                continue;
+            };
+            if !keep_edge(e.id()) {
+                continue;
+            }
+            let new_edge = g.core.add_edge(from, to, *e.weight());
+            if let Some(r) = edge_refs.get(&e.id()) {
+                g.runtime_ref.edges.push(Ref {
+                    name: r.name.clone(),
+                    uuid: r.uuid,
+                    index: new_edge,
+                });
+                if let Some(data) = self.edge_data.get(&r.uuid) {
+                    g.edge_data.insert(r.uuid, data.clone());
+                }
+                kept.insert(r.uuid);
+                kinds_present.insert(r.name.as_str());
+            }
+        }
+
+        for kind in &self.edge_kinds {
+            if kinds_present.contains(kind.name.as_str()) {
+                let kind = kind.clone();
+                g.runtime_ref.edge_kinds.push(EdgeKindRef {
+                    name: kind.name.clone(),
+                    uuid: kind.metadata.id,
+                });
+                g.edge_kinds.push(kind);
+            }
+        }
+
+        for (&old, &new) in &remap {
+            let connections: Vec<Connection> = self.core[old]
+                .connections
+                .iter()
+                .filter(|c| c.instance.is_some_and(|i| kept.contains(&i)))
+                .filter_map(|c| {
+                    remap.get(&c.node.index).map(|&index| Connection {
+                        node: Ref {
+                            index,
+                            ..c.node.clone()
+                        },
+                        ..c.clone()
+                    })
+                })
+                .collect();
+            g.core[new].connections = connections;
+        }
+
+        g.runtime_ref.nodes = g.core.node_indices().map(|idx| g.node_ref(idx)).collect();
+        g.recompute_energy();
+        g.rebuild_indexes();
+        g
+    }
+}
@@ -0,0 +1,3 @@
+pub mod algorithms;
+pub mod models;
+pub mod runtime;
@@ -1,14 +1,82 @@
 use petgraph::Undirected;
 
-use crate::models::graph_models::Grapho;
-use crate::models::graph_models::Node;
-use crate::models::node_types::NodeType;
+use graphos::models::graph_models::Grapho;
+use graphos::models::graph_models::Node;
+use graphos::models::node_types::NodeType;
+use graphos::runtime;
 
-mod models;
-mod runtime;
 const BASEPATH: &str = "./files";
 
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match (args.first().map(String::as_str), args.len()) {
+        (None, _) => demo(),
+        (Some("stats"), 1 | 2) => stats(args.get(1)),
+        (Some("validate"), 1 | 2) => validate(args.get(1)),
+        (Some("diff"), 3) => diff(&args[1], &args[2]),
+        _ => usage(),
+    }
+}
+
+// Anything unrecognised must not fall through to the demo, which overwrites ./files.
+fn usage() -> ! {
+    eprintln!("Usage:");
+    eprintln!(
+        "  graphos                     write the demo graph to {}",
+        BASEPATH
+    );
+    eprintln!("  graphos stats [path]");
+    eprintln!("  graphos validate [path]");
+    eprintln!("  graphos diff <old> <new>");
+    std::process::exit(2);
+}
+
+// Usage: graphos stats [path]
+fn stats(path: Option<&String>) {
+    let path = path
+        .cloned()
+        .unwrap_or_else(|| format!("{}/graphos.json", BASEPATH));
+    match Grapho::<NodeType, Undirected>::load_from_file(&path) {
+        Ok(grapho) => print!("{}", grapho.stats()),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+// Usage: graphos validate [path]
+fn validate(path: Option<&String>) {
+    let path = path
+        .cloned()
+        .unwrap_or_else(|| format!("{}/graphos.json", BASEPATH));
+    let grapho = Grapho::<NodeType, Undirected>::load_from_file(&path).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+    match grapho.validate() {
+        Ok(()) => println!("{}: valid", path),
+        Err(errors) => {
+            for e in &errors {
+                eprintln!("{}", e);
+            }
+            std::process::exit(1);
+        }
+    }
+}
+
+// Usage: graphos diff <old> <new>
+fn diff(old: &str, new: &str) {
+    let load = |path: &str| {
+        Grapho::<NodeType, Undirected>::load_from_file(path).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        })
+    };
+    print!("{}", load(old).diff(&load(new)));
+}
+
+fn demo() {
     let mut grapho = Grapho::<NodeType, Undirected>::new("graphos");
     let _ = grapho.add_connection(
         Node::new(
@@ -0,0 +1,314 @@
+use std::fmt;
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use petgraph::graph::{EdgeIndex, NodeIndex};
+use petgraph::{Direction, EdgeType};
+use serde::{Deserialize, Serialize};
+
+use crate::algorithms::centrality::Centrality;
+use crate::models::graph_models::{ConnectionDirection, EdgeKind, Grapho, Ref};
+use crate::models::node_types::NodeTypes;
+
+/// Decides how `Node::energy` and `EdgeKind::energy` are computed for a `Grapho`.
+pub trait EnergyPolicy<T: NodeTypes, Ty: EdgeType>: fmt::Debug + Send + Sync {
+    fn node_energy(&self, graph: &Grapho<T, Ty>, node: NodeIndex, now: DateTime<Utc>) -> f64;
+
+    fn node_energies(&self, graph: &Grapho<T, Ty>, now: DateTime<Utc>) -> Vec<f64> {
+        graph
+            .core
+            .node_indices()
+            .map(|idx| self.node_energy(graph, idx, now))
+            .collect()
+    }
+
+    fn edge_kind_energy(&self, graph: &Grapho<T, Ty>, kind: &EdgeKind, _now: DateTime<Utc>) -> f64 {
+        edges_of_kind(graph, &kind.name).count() as f64
+    }
+
+    /// Local policies only change the endpoints of a new connection; global ones
+    /// (e.g. centrality) mark the graph stale and are recomputed by
+    /// `Grapho::refresh_energy`.
+    fn is_local(&self) -> bool {
+        true
+    }
+
+    /// How the policy is persisted with the graph; `Custom` ones cannot be
+    /// restored and load as degree.
+    fn spec(&self) -> EnergySpec {
+        EnergySpec::Custom
+    }
+}
+
+/// Serializable description of a built-in policy, saved with the graph so a
+/// reload keeps the energy on the same scale.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum EnergySpec {
+    #[default]
+    Degree,
+    OutDegree,
+    InDegree,
+    Weighted,
+    TimeDecayed {
+        half_life_ms: i64,
+        access_weight: f64,
+    },
+    AccessCount,
+    Centrality(Centrality),
+    Custom, // FnEnergy and other caller-defined policies
+}
+
+impl EnergySpec {
+    /// The policy this spec describes, or `None` for `Custom`.
+    pub fn policy<T, Ty>(&self) -> Option<Arc<dyn EnergyPolicy<T, Ty>>>
+    where
+        T: NodeTypes + Clone + Serialize + for<'de> Deserialize<'de>,
+        Ty: EdgeType + Serialize + for<'de> Deserialize<'de>,
+    {
+        let policy: Arc<dyn EnergyPolicy<T, Ty>> = match *self {
+            EnergySpec::Degree => Arc::new(DegreeEnergy),
+            EnergySpec::OutDegree => Arc::new(OutDegreeEnergy),
+            EnergySpec::InDegree => Arc::new(InDegreeEnergy),
+            EnergySpec::Weighted => Arc::new(WeightedDegreeEnergy),
+            EnergySpec::TimeDecayed {
+                half_life_ms,
+                access_weight,
+            } => Arc::new(
+                TimeDecayedEnergy::new(Duration::milliseconds(half_life_ms))
+                    .with_access_weight(access_weight),
+            ),
+            EnergySpec::AccessCount => Arc::new(AccessCountEnergy),
+            EnergySpec::Centrality(measure) => Arc::new(measure),
+            EnergySpec::Custom => return None,
+        };
+        Some(policy)
+    }
+}
+
+pub(crate) fn edges_of_kind<'a, T: NodeTypes, Ty: EdgeType>(
+    graph: &'a Grapho<T, Ty>,
+    name: &'a str,
+) -> impl Iterator<Item = EdgeIndex> + 'a {
+    graph
+        .runtime_ref
+        .edges
+        .iter()
+        .filter(move |r| r.name == name)
+        .map(|r| r.index)
+}
+
+/// `created_at` of the connections backing an edge instance, if recorded.
+pub(crate) fn edge_created_at<T: NodeTypes, Ty: EdgeType>(
+    graph: &Grapho<T, Ty>,
+    edge: &Ref<EdgeIndex>,
+) -> Option<DateTime<Utc>> {
+    let (source, target) = graph.core.edge_endpoints(edge.index)?;
+    graph.core[source]
+        .connections
+        .iter()
+        .find(|c| c.node.index == target && c.instance == Some(edge.uuid))
+        .and_then(|c| c.created_at)
+}
+
+// -----------POLICIES---------------------------------
+/// Number of entries in `Node::connections` (the default).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DegreeEnergy;
+
+impl<T: NodeTypes, Ty: EdgeType> EnergyPolicy<T, Ty> for DegreeEnergy {
+    fn node_energy(&self, graph: &Grapho<T, Ty>, node: NodeIndex, _now: DateTime<Utc>) -> f64 {
+        graph.core[node].connections.len() as f64
+    }
+
+    fn spec(&self) -> EnergySpec {
+        EnergySpec::Degree
+    }
+}
+
+/// Connections leaving the node; the plain degree in undirected graphs.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OutDegreeEnergy;
+
+impl<T: NodeTypes, Ty: EdgeType> EnergyPolicy<T, Ty> for OutDegreeEnergy {
+    fn node_energy(&self, graph: &Grapho<T, Ty>, node: NodeIndex, _now: DateTime<Utc>) -> f64 {
+        graph.core[node]
+            .connections
+            .iter()
+            .filter(|c| c.direction != ConnectionDirection::Incoming)
+            .count() as f64
+    }
+
+    fn spec(&self) -> EnergySpec {
+        EnergySpec::OutDegree
+    }
+}
+
+/// Connections arriving at the node; the plain degree in undirected graphs.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InDegreeEnergy;
+
+impl<T: NodeTypes, Ty: EdgeType> EnergyPolicy<T, Ty> for InDegreeEnergy {
+    fn node_energy(&self, graph: &Grapho<T, Ty>, node: NodeIndex, _now: DateTime<Utc>) -> f64 {
+        graph.core[node]
+            .connections
+            .iter()
+            .filter(|c| c.direction != ConnectionDirection::Outgoing)
+            .count() as f64
+    }
+
+    fn spec(&self) -> EnergySpec {
+        EnergySpec::InDegree
+    }
+}
+
+/// Sum of the `core` edge weights incident to the node.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WeightedDegreeEnergy;
+
+impl<T: NodeTypes, Ty: EdgeType> EnergyPolicy<T, Ty> for WeightedDegreeEnergy {
+    fn node_energy(&self, graph: &Grapho<T, Ty>, node: NodeIndex, _now: DateTime<Utc>) -> f64 {
+        let outgoing = graph.core.edges_directed(node, Direction::Outgoing);
+        let total: u64 = if graph.core.is_directed() {
+            outgoing
+                .chain(graph.core.edges_directed(node, Direction::Incoming))
+                .map(|e| *e.weight() as u64)
+                .sum()
+        } else {
+            outgoing.map(|e| *e.weight() as u64).sum()
+        };
+        total as f64
+    }
+
+    fn edge_kind_energy(&self, graph: &Grapho<T, Ty>, kind: &EdgeKind, _now: DateTime<Utc>) -> f64 {
+        edges_of_kind(graph, &kind.name)
+            .filter_map(|e| graph.core.edge_weight(e))
+            .map(|w| *w as f64)
+            .sum()
+    }
+
+    fn spec(&self) -> EnergySpec {
+        EnergySpec::Weighted
+    }
+}
+
+/// Every connection (and, optionally, every recorded access) counts for less
+/// the older it is, halving each `half_life`.
+#[derive(Debug, Clone, Copy)]
+pub struct TimeDecayedEnergy {
+    pub half_life: Duration,
+    pub access_weight: f64,
+}
+
+impl TimeDecayedEnergy {
+    pub fn new(half_life: Duration) -> Self {
+        Self {
+            half_life,
+            access_weight: 0.0,
+        }
+    }
+
+    /// Adds every recorded access, each decayed from its own timestamp and
+    /// scaled by `weight`.
+    pub fn with_access_weight(mut self, weight: f64) -> Self {
+        self.access_weight = weight;
+        self
+    }
+
+    pub fn decay(&self, since: DateTime<Utc>, now: DateTime<Utc>) -> f64 {
+        let half_life = self.half_life.num_milliseconds();
+        if half_life <= 0 {
+            return 1.0;
+        }
+        let age = (now - since).num_milliseconds().max(0);
+        0.5f64.powf(age as f64 / half_life as f64)
+    }
+}
+
+impl<T: NodeTypes, Ty: EdgeType> EnergyPolicy<T, Ty> for TimeDecayedEnergy {
+    fn node_energy(&self, graph: &Grapho<T, Ty>, node: NodeIndex, now: DateTime<Utc>) -> f64 {
+        let node = &graph.core[node];
+        let connections: f64 = node
+            .connections
+            .iter()
+            .map(|c| self.decay(c.created_at.unwrap_or(node.metadata.created_at), now))
+            .sum();
+        if self.access_weight == 0.0 {
+            return connections;
+        }
+        let recent: f64 = node
+            .recent_accesses
+            .iter()
+            .map(|&at| self.decay(at, now))
+            .sum();
+        // accesses that fell out of the history happened no later than its oldest entry
+        let older = node
+            .access_count
+            .saturating_sub(node.recent_accesses.len() as u64);
+        let older = match node
+            .recent_accesses
+            .front()
+            .copied()
+            .or(node.last_accessed_at)
+        {
+            Some(at) => older as f64 * self.decay(at, now),
+            None => 0.0,
+        };
+        connections + self.access_weight * (recent + older)
+    }
+
+    fn edge_kind_energy(&self, graph: &Grapho<T, Ty>, kind: &EdgeKind, now: DateTime<Utc>) -> f64 {
+        graph
+            .runtime_ref
+            .edges
+            .iter()
+            .filter(|r| r.name == kind.name && graph.core.edge_weight(r.index).is_some())
+            .map(|r| {
+                let created_at = edge_created_at(graph, r).unwrap_or(kind.metadata.created_at);
+                self.decay(created_at, now)
+            })
+            .sum()
+    }
+
+    fn spec(&self) -> EnergySpec {
+        EnergySpec::TimeDecayed {
+            half_life_ms: self.half_life.num_milliseconds(),
+            access_weight: self.access_weight,
+        }
+    }
+}
+
+/// Number of times the node was read through `Grapho::record_access`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AccessCountEnergy;
+
+impl<T: NodeTypes, Ty: EdgeType> EnergyPolicy<T, Ty> for AccessCountEnergy {
+    fn node_energy(&self, graph: &Grapho<T, Ty>, node: NodeIndex, _now: DateTime<Utc>) -> f64 {
+        graph.core[node].access_count as f64
+    }
+
+    fn spec(&self) -> EnergySpec {
+        EnergySpec::AccessCount
+    }
+}
+
+/// Wraps a closure so callers can plug in their own node energy.
+pub struct FnEnergy<F>(pub F);
+
+impl<F> fmt::Debug for FnEnergy<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("FnEnergy")
+    }
+}
+
+impl<T: NodeTypes, Ty: EdgeType, F> EnergyPolicy<T, Ty> for FnEnergy<F>
+where
+    F: Fn(&Grapho<T, Ty>, NodeIndex, DateTime<Utc>) -> f64 + Send + Sync,
+{
+    fn node_energy(&self, graph: &Grapho<T, Ty>, node: NodeIndex, now: DateTime<Utc>) -> f64 {
+        (self.0)(graph, node, now)
+    }
+
+    fn is_local(&self) -> bool {
+        false
+    }
+}
@@ -1,17 +1,25 @@
-use crate::models::node_types::NodeTypes;
+use crate::models::energy::{DegreeEnergy, EnergyPolicy, EnergySpec};
+use crate::models::node_types::{NodeType, NodeTypes};
+use crate::models::props::{PropFilter, Props};
+use crate::models::schema::{Schema, SchemaError};
+use crate::models::secondary_index::{IndexKind, IndexTarget, SecondaryIndex};
+use crate::models::text_index::TextIndex;
+use crate::models::vector_index::{VectorIndex, VectorMetric};
 use chrono::{DateTime, Utc};
 use petgraph::prelude::EdgeIndex;
 use petgraph::{EdgeType, Graph, graph::NodeIndex};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::fmt;
 use std::fs;
+use std::ops::RangeBounds;
+use std::sync::Arc;
 use uuid::Uuid;
 
 // -----------RUNTIME-REF---------------------------------
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RuntimeRef {
-
-    pub edges: Vec<Ref<EdgeIndex>>, 
+    pub edges: Vec<Ref<EdgeIndex>>,
     pub nodes: Vec<Ref<NodeIndex>>,
     pub edge_kinds: Vec<EdgeKindRef>, // reference to edge kind id by name
 }
@@ -24,8 +32,26 @@ pub struct Grapho<T: NodeTypes, Ty: EdgeType> {
     pub runtime_ref: RuntimeRef,
     pub metadata: Metadata,
     pub edge_kinds: Vec<EdgeKind>, // reusable kinds (e.g., friendship)
+    #[serde(default)]
+    pub edge_data: BTreeMap<Uuid, EdgeData>, // per-instance metadata, keyed like runtime_ref.edges
+    #[serde(skip, default = "default_energy_policy")]
+    energy_policy: Arc<dyn EnergyPolicy<T, Ty>>, // rebuilt from energy_spec on load
+    #[serde(default)]
+    energy_spec: EnergySpec,
+    #[serde(skip)]
+    energy_stale: bool, // a global policy has pending changes; see refresh_energy
+    #[serde(default)]
+    vector_index: VectorIndex, // HNSW over node embeddings
+    #[serde(default)]
+    text_index: TextIndex, // BM25 over names, contents and descriptions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    schema: Option<Schema>, // checked by add_connection and validate
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    indexes: BTreeMap<String, SecondaryIndex>, // declarations only, entries rebuilt on load
     #[serde(skip)]
     node_index_by_name: HashMap<String, NodeIndex>,
+    #[serde(skip)]
+    label_index: HashMap<String, BTreeSet<NodeIndex>>,
 }
 
 impl<T: NodeTypes, Ty: EdgeType> Grapho<T, Ty>
@@ -47,32 +73,183 @@ where
             },
             metadata: Metadata::new(description),
             edge_kinds: vec![],
+            edge_data: BTreeMap::new(),
+            energy_policy: default_energy_policy(),
+            energy_spec: EnergySpec::Degree,
+            energy_stale: false,
+            vector_index: VectorIndex::default(),
+            text_index: TextIndex::default(),
+            schema: None,
+            indexes: BTreeMap::new(),
             node_index_by_name: HashMap::new(),
+            label_index: HashMap::new(),
         }
     }
 
+    /// An empty graph named `name` with the energy policy, schema, index
+    /// declarations and vector metric of `self`.
+    pub(crate) fn empty_like(&self, name: &str) -> Self {
+        let mut g = Self::new(name);
+        g.share_energy_policy(self);
+        g.set_schema(self.schema().cloned());
+        g.share_indexes(self);
+        g.set_vector_metric(self.vector_metric());
+        g
+    }
+
     pub fn add_connection(
         &mut self,
         from: Node<T>,
         to: Node<T>,
         name: &str,
         description: &str,
-    ) -> Result<(), String> {
-        let from_idx = self.get_or_add_node(&from);
-        let to_idx = self.get_or_add_node(&to);
-        if let Some(edge_ref) = self.find_existing_connection(name, from_idx, to_idx) {
-            self.ensure_connections_present(from_idx, to_idx, &from, &to, edge_ref);
+    ) -> Result<(), ConnectionError> {
+        self.check_embedding_dimensions(&from, &to)?;
+        let existing = match (
+            self.get_node_index_by_name(&from.name),
+            self.get_node_index_by_name(&to.name),
+        ) {
+            (Some(from_idx), Some(to_idx)) => self.find_existing_connection(name, from_idx, to_idx),
+            _ => None,
+        };
+        self.check_connection(&from, &to, name, existing.is_none())?;
+        let from_idx = self.get_or_add_node(&from)?;
+        let to_idx = self.get_or_add_node(&to)?;
+        if let Some(existing) = existing {
+            self.ensure_connections_present(from_idx, to_idx, &from, &to, existing);
             self.update_metrics_and_sort(from_idx, to_idx);
             return Ok(());
         }
+        self.add_edge_instance(from_idx, to_idx, &from, &to, name, description);
+        Ok(())
+    }
+
+    /// Like `add_connection`, but always adds a new `name` edge even when the two
+    /// nodes are already linked by that kind. Returns the new instance uuid.
+    pub fn add_parallel_connection(
+        &mut self,
+        from: Node<T>,
+        to: Node<T>,
+        name: &str,
+        description: &str,
+    ) -> Result<Uuid, ConnectionError> {
+        self.check_embedding_dimensions(&from, &to)?;
+        self.check_connection(&from, &to, name, true)?;
+        let from_idx = self.get_or_add_node(&from)?;
+        let to_idx = self.get_or_add_node(&to)?;
+        Ok(self.add_edge_instance(from_idx, to_idx, &from, &to, name, description))
+    }
+
+    /// Every connection from `a` to `b`, one per edge instance, whatever the kind.
+    pub fn connections_between(&self, a: NodeIndex, b: NodeIndex) -> Vec<&Connection> {
+        self.core[a]
+            .connections
+            .iter()
+            .filter(|c| c.node.index == b)
+            .collect()
+    }
+
+    /// Connections leaving `node`: outgoing ones, or all of them in undirected graphs.
+    pub fn outgoing(&self, node: NodeIndex) -> Vec<&Connection> {
+        self.core[node]
+            .connections
+            .iter()
+            .filter(|c| c.direction != ConnectionDirection::Incoming)
+            .collect()
+    }
+
+    /// Connections arriving at `node`: incoming ones, or all of them in undirected graphs.
+    pub fn incoming(&self, node: NodeIndex) -> Vec<&Connection> {
+        self.core[node]
+            .connections
+            .iter()
+            .filter(|c| c.direction != ConnectionDirection::Outgoing)
+            .collect()
+    }
+
+    // Direction recorded on the source (`outgoing`) or target end of a new edge.
+    fn direction_at(&self, outgoing: bool) -> ConnectionDirection {
+        match (self.core.is_directed(), outgoing) {
+            (false, _) => ConnectionDirection::Both,
+            (true, true) => ConnectionDirection::Outgoing,
+            (true, false) => ConnectionDirection::Incoming,
+        }
+    }
+
+    // Checked before either endpoint is added, so a rejected pair leaves no
+    // half-added node behind. While the index is empty the endpoints must agree
+    // with each other.
+    fn check_embedding_dimensions(
+        &self,
+        from: &Node<T>,
+        to: &Node<T>,
+    ) -> Result<(), ConnectionError> {
+        let mut expected = self.vector_index.dimensions();
+        for node in [from, to] {
+            let Some(embedding) = node_embedding(node) else {
+                continue;
+            };
+            match expected {
+                Some(expected) if embedding.len() != expected => {
+                    return Err(ConnectionError::EmbeddingDimensions {
+                        node: node.name.clone(),
+                        expected,
+                        found: embedding.len(),
+                    });
+                }
+                _ => expected = Some(embedding.len()),
+            }
+        }
+        Ok(())
+    }
+
+    // Adds or replaces the vector of `idx`, refusing one whose size disagrees
+    // with the vectors already indexed.
+    fn index_embedding(
+        &mut self,
+        idx: NodeIndex,
+        name: &str,
+        embedding: &[f32],
+    ) -> Result<(), ConnectionError> {
+        let expected = self.vector_index.dimensions();
+        self.vector_index
+            .insert(idx, embedding.to_vec())
+            .map_err(|_| ConnectionError::EmbeddingDimensions {
+                node: name.to_string(),
+                expected: expected.unwrap_or_default(),
+                found: embedding.len(),
+            })
+    }
+
+    pub fn schema(&self) -> Option<&Schema> {
+        self.schema.as_ref()
+    }
+
+    /// Installs or removes the schema. Existing nodes are not checked; call `validate`.
+    pub fn set_schema(&mut self, schema: Option<Schema>) {
+        self.schema = schema;
+    }
+
+    fn add_edge_instance(
+        &mut self,
+        from_idx: NodeIndex,
+        to_idx: NodeIndex,
+        from: &Node<T>,
+        to: &Node<T>,
+        name: &str,
+        description: &str,
+    ) -> Uuid {
         let edge_index = self.add_core_edge(from_idx, to_idx);
         let edge_kind_id = self.get_or_create_edge_kind(name, description);
-        self.increment_edge_kind_energy(edge_kind_id);
-        self.ensure_runtime_edge_instance(name, edge_index);
+        let instance = self.ensure_runtime_edge_instance(name, edge_index);
+        self.edge_data
+            .entry(instance)
+            .or_insert_with(|| EdgeData::new(instance, Utc::now()));
+        self.refresh_edge_kind_energy(edge_kind_id);
         self.ensure_runtime_edge_kind(name, edge_kind_id);
-        self.push_connection(from_idx, to_idx, &from, &to, name, edge_kind_id);
+        self.push_connection(from_idx, to_idx, from, to, name, (edge_kind_id, instance));
         self.update_metrics_and_sort(from_idx, to_idx);
-        Ok(())
+        instance
     }
 
     fn find_existing_connection(
@@ -80,19 +257,23 @@ where
         name: &str,
         from_idx: NodeIndex,
         to_idx: NodeIndex,
-    ) -> Option<Ref<Uuid>> {
+    ) -> Option<Connection> {
         self.core[from_idx]
             .connections
             .iter()
-            .find(|c| c.edge.name == name && c.node.index == to_idx)
-            .map(|c| c.edge.clone())
+            .find(|c| {
+                c.edge.name == name
+                    && c.node.index == to_idx
+                    && c.direction != ConnectionDirection::Incoming
+            })
             .or_else(|| {
-                self.core[to_idx]
-                    .connections
-                    .iter()
-                    .find(|c| c.edge.name == name && c.node.index == from_idx)
-                    .map(|c| c.edge.clone())
+                self.core[to_idx].connections.iter().find(|c| {
+                    c.edge.name == name
+                        && c.node.index == from_idx
+                        && c.direction != ConnectionDirection::Outgoing
+                })
             })
+            .cloned()
     }
 
     fn ensure_connections_present(
@@ -101,15 +282,18 @@ where
         to_idx: NodeIndex,
         from: &Node<T>,
         to: &Node<T>,
-        edge_ref: Ref<Uuid>,
+        existing: Connection,
     ) {
+        let (edge_ref, instance) = (existing.edge, existing.instance);
+        let (out, inc) = (self.direction_at(true), self.direction_at(false));
         let from_uuid = self.core[from_idx].metadata.id;
         let to_uuid = self.core[to_idx].metadata.id;
-        if !self.core[from_idx]
-            .connections
-            .iter()
-            .any(|r| r.node.index == to_idx)
-        {
+        if !self.core[from_idx].connections.iter().any(|r| {
+            r.node.index == to_idx
+                && r.edge.name == edge_ref.name
+                && r.instance == instance
+                && r.direction == out
+        }) {
             self.core[from_idx].connections.push(Connection {
                 node: Ref {
                     name: to.name.clone(),
@@ -117,13 +301,18 @@ where
                     index: to_idx,
                 },
                 edge: edge_ref.clone(),
+                instance,
+                direction: out,
+                created_at: Some(Utc::now()),
             });
+            self.core[from_idx].metadata.update();
         }
-        if !self.core[to_idx]
-            .connections
-            .iter()
-            .any(|r| r.node.index == from_idx)
-        {
+        if !self.core[to_idx].connections.iter().any(|r| {
+            r.node.index == from_idx
+                && r.edge.name == edge_ref.name
+                && r.instance == instance
+                && r.direction == inc
+        }) {
             self.core[to_idx].connections.push(Connection {
                 node: Ref {
                     name: from.name.clone(),
@@ -131,7 +320,11 @@ where
                     index: from_idx,
                 },
                 edge: edge_ref,
+                instance,
+                direction: inc,
+                created_at: Some(Utc::now()),
             });
+            self.core[to_idx].metadata.update();
         }
     }
 
@@ -149,24 +342,37 @@ where
         id
     }
 
-    fn increment_edge_kind_energy(&mut self, edge_kind_id: Uuid) {
-        if let Some(kind) = self
+    fn refresh_edge_kind_energy(&mut self, edge_kind_id: Uuid) {
+        let Some(pos) = self
             .edge_kinds
-            .iter_mut()
-            .find(|k| k.metadata.id == edge_kind_id)
-        {
-            kind.energy = kind.energy.saturating_add(1);
-        }
+            .iter()
+            .position(|k| k.metadata.id == edge_kind_id)
+        else {
+            return;
+        };
+        self.edge_kinds[pos].metadata.update();
+        let energy = self
+            .energy_policy
+            .edge_kind_energy(self, &self.edge_kinds[pos], Utc::now());
+        self.edge_kinds[pos].energy = energy;
     }
 
-    fn ensure_runtime_edge_instance(&mut self, name: &str, edge_index: EdgeIndex) {
-        if !self.runtime_ref.edges.iter().any(|r| r.index == edge_index) {
-            self.runtime_ref.edges.push(Ref {
-                name: name.to_string(),
-                uuid: Uuid::new_v4(),
-                index: edge_index,
-            });
+    fn ensure_runtime_edge_instance(&mut self, name: &str, edge_index: EdgeIndex) -> Uuid {
+        if let Some(r) = self
+            .runtime_ref
+            .edges
+            .iter()
+            .find(|r| r.index == edge_index)
+        {
+            return r.uuid;
         }
+        let uuid = Uuid::new_v4();
+        self.runtime_ref.edges.push(Ref {
+            name: name.to_string(),
+            uuid,
+            index: edge_index,
+        });
+        uuid
     }
 
     fn ensure_runtime_edge_kind(&mut self, name: &str, edge_kind_id: Uuid) {
@@ -190,14 +396,15 @@ where
         from: &Node<T>,
         to: &Node<T>,
         name: &str,
-        edge_kind_id: Uuid,
+        (edge_kind_id, instance): (Uuid, Uuid),
     ) {
+        let (out, inc) = (self.direction_at(true), self.direction_at(false));
         let from_uuid = self.core[from_idx].metadata.id;
         let to_uuid = self.core[to_idx].metadata.id;
         if !self.core[from_idx]
             .connections
             .iter()
-            .any(|r| r.node.index == to_idx)
+            .any(|r| r.node.index == to_idx && r.instance == Some(instance) && r.direction == out)
         {
             self.core[from_idx].connections.push(Connection {
                 node: Ref {
@@ -210,12 +417,16 @@ where
                     uuid: edge_kind_id,
                     index: edge_kind_id,
                 },
+                instance: Some(instance),
+                direction: out,
+                created_at: Some(Utc::now()),
             });
+            self.core[from_idx].metadata.update();
         }
         if !self.core[to_idx]
             .connections
             .iter()
-            .any(|r| r.node.index == from_idx)
+            .any(|r| r.node.index == from_idx && r.instance == Some(instance) && r.direction == inc)
         {
             self.core[to_idx].connections.push(Connection {
                 node: Ref {
@@ -228,35 +439,162 @@ where
                     uuid: edge_kind_id,
                     index: edge_kind_id,
                 },
+                instance: Some(instance),
+                direction: inc,
+                created_at: Some(Utc::now()),
             });
+            self.core[to_idx].metadata.update();
         }
     }
 
     fn update_metrics_and_sort(&mut self, from_idx: NodeIndex, to_idx: NodeIndex) {
         self.core[from_idx].node_index = from_idx;
         self.core[to_idx].node_index = to_idx;
-        self.core[from_idx].energy = self.core[from_idx].connections.len() as u32;
-        self.core[to_idx].energy = self.core[to_idx].connections.len() as u32;
-        self.runtime_ref
-            .nodes
-            .sort_by(|a, b| self.core[b.index].energy.cmp(&self.core[a.index].energy));
+        if !self.energy_policy.is_local() {
+            // global measures shift for every node; defer to refresh_energy
+            self.energy_stale = true;
+            return;
+        }
+        let now = Utc::now();
+        self.core[from_idx].energy = self.energy_policy.node_energy(self, from_idx, now);
+        self.core[to_idx].energy = self.energy_policy.node_energy(self, to_idx, now);
+        self.sort_runtime_nodes();
+    }
+
+    fn sort_runtime_nodes(&mut self) {
+        self.runtime_ref.nodes.sort_by(|a, b| {
+            self.core[b.index]
+                .energy
+                .total_cmp(&self.core[a.index].energy)
+        });
     }
 
-    fn get_or_add_node(&mut self, node: &Node<T>) -> NodeIndex {
+    fn get_or_add_node(&mut self, node: &Node<T>) -> Result<NodeIndex, ConnectionError> {
         if let Some(idx) = self.node_index_by_name.get(&node.name).copied() {
             self.core[idx].node_index = idx;
-            return idx;
+            return Ok(idx);
+        }
+        // `Graph` hands out the next index, so the embedding can be indexed (or
+        // refused) before anything else changes.
+        let embedding = node_embedding(node);
+        if let Some(embedding) = &embedding {
+            let next = NodeIndex::new(self.core.node_count());
+            self.index_embedding(next, &node.name, embedding)?;
         }
         let idx = self.core.add_node(node.clone());
         self.core[idx].node_index = idx;
-        self.core[idx].energy = self.core[idx].connections.len() as u32;
+        self.core[idx].embedding = embedding;
+        if self.energy_policy.is_local() {
+            self.core[idx].energy = self.energy_policy.node_energy(self, idx, Utc::now());
+        } else {
+            self.energy_stale = true;
+        }
         self.runtime_ref.nodes.push(Ref {
             name: node.name.clone(),
             uuid: node.metadata.id,
             index: idx,
         });
         self.node_index_by_name.insert(node.name.clone(), idx);
-        idx
+        for label in &node.labels {
+            self.label_index
+                .entry(label.clone())
+                .or_default()
+                .insert(idx);
+        }
+        self.index_node(idx);
+        self.text_index.insert(idx, &search_document(node));
+        Ok(idx)
+    }
+
+    pub fn set_node_embedding(
+        &mut self,
+        idx: NodeIndex,
+        embedding: Vec<f32>,
+    ) -> Result<(), String> {
+        if self.core.node_weight(idx).is_none() {
+            return Err(format!("Node not found: {}", idx.index()));
+        }
+        self.vector_index.insert(idx, embedding.clone())?;
+        self.core[idx].embedding = Some(embedding);
+        self.core[idx].metadata.update();
+        Ok(())
+    }
+
+    /// Top-k nodes closest to `query`. Uses the HNSW index when `metric` matches
+    /// the index metric and falls back to an exact scan otherwise. Scores are
+    /// similarities for cosine/dot and distances for L2.
+    pub fn nearest_nodes(
+        &self,
+        query: &[f32],
+        k: usize,
+        metric: VectorMetric,
+    ) -> Vec<(Ref<NodeIndex>, f32)> {
+        let hits: Vec<NodeIndex> = if metric == self.vector_index.metric {
+            self.vector_index
+                .search(query, k)
+                .into_iter()
+                .map(|(idx, _)| idx)
+                .collect()
+        } else {
+            let mut scan: Vec<(NodeIndex, f32)> = self
+                .core
+                .node_indices()
+                .filter_map(|idx| {
+                    let embedding = self.core[idx].embedding.as_ref()?;
+                    (embedding.len() == query.len())
+                        .then(|| (idx, metric.distance(query, embedding)))
+                })
+                .collect();
+            scan.sort_by(|a, b| a.1.total_cmp(&b.1));
+            scan.into_iter().take(k).map(|(idx, _)| idx).collect()
+        };
+        hits.into_iter()
+            .filter_map(|idx| {
+                let embedding = self.core[idx].embedding.as_ref()?;
+                Some((self.node_ref(idx), metric.score(query, embedding)))
+            })
+            .collect()
+    }
+
+    pub fn vector_metric(&self) -> VectorMetric {
+        self.vector_index.metric
+    }
+
+    /// Switches the metric used by the index and rebuilds it.
+    pub fn set_vector_metric(&mut self, metric: VectorMetric) {
+        self.vector_index.metric = metric;
+        self.rebuild_vector_index();
+    }
+
+    /// Full-text search over node names, text content and descriptions, best
+    /// match first. Query words also match longer words they start.
+    pub fn search(&self, query: &str) -> Vec<(Ref<NodeIndex>, f32)> {
+        self.text_index
+            .search(query)
+            .into_iter()
+            .map(|(idx, score)| (self.node_ref(idx), score))
+            .collect()
+    }
+
+    // Keeps a loaded index unless it was built from other text than the nodes hold.
+    fn restore_text_index(&mut self) {
+        let docs: Vec<_> = self
+            .core
+            .node_indices()
+            .map(|idx| (idx, search_document(&self.core[idx])))
+            .collect();
+        if !self.text_index.restore(&docs) {
+            self.text_index.rebuild(docs);
+        }
+    }
+
+    fn rebuild_vector_index(&mut self) {
+        let items: Vec<_> = self
+            .core
+            .node_indices()
+            .filter_map(|idx| Some((idx, self.core[idx].embedding.clone()?)))
+            .collect();
+        self.vector_index.rebuild(items);
     }
 
     pub fn calculate_distance(&self, from: NodeIndex, to: NodeIndex) -> Result<u32, String> {
@@ -272,11 +610,376 @@ where
         self.node_index_by_name.get(name).copied()
     }
 
+    /// Adds `node` without its connections, keeping its metadata; used when
+    /// assembling a graph out of other graphs.
+    pub(crate) fn insert_node(&mut self, node: &Node<T>) -> Result<NodeIndex, ConnectionError> {
+        let mut node = node.clone();
+        node.connections.clear();
+        self.get_or_add_node(&node)
+    }
+
+    /// Adds a core edge for an existing edge kind and edge instance id. A kind
+    /// with the same name already in this graph is reused.
+    pub(crate) fn insert_edge_instance(
+        &mut self,
+        from_idx: NodeIndex,
+        to_idx: NodeIndex,
+        edge_uuid: Uuid,
+        edge: &EdgeInstance<'_>,
+    ) -> EdgeIndex {
+        let (kind, created_at) = (edge.kind, edge.created_at);
+        let edge_index = self.core.add_edge(from_idx, to_idx, edge.weight);
+        let kind_id = match self.edge_kinds.iter().find(|k| k.name == kind.name) {
+            Some(existing) => existing.metadata.id,
+            None => {
+                self.edge_kinds.push(EdgeKind {
+                    energy: 0.0,
+                    ..kind.clone()
+                });
+                kind.metadata.id
+            }
+        };
+        self.runtime_ref.edges.push(Ref {
+            name: kind.name.clone(),
+            uuid: edge_uuid,
+            index: edge_index,
+        });
+        self.ensure_runtime_edge_kind(&kind.name, kind_id);
+        let data = match edge.data {
+            Some(data) => data.clone(),
+            None => EdgeData::new(edge_uuid, created_at.unwrap_or_else(Utc::now)),
+        };
+        self.edge_data.insert(edge_uuid, data);
+        for (a, b, outgoing) in [(from_idx, to_idx, true), (to_idx, from_idx, false)] {
+            let direction = self.direction_at(outgoing);
+            if !self.core[a].connections.iter().any(|c| {
+                c.node.index == b && c.instance == Some(edge_uuid) && c.direction == direction
+            }) {
+                let node = self.node_ref(b);
+                self.core[a].connections.push(Connection {
+                    node,
+                    edge: Ref {
+                        name: kind.name.clone(),
+                        uuid: kind_id,
+                        index: kind_id,
+                    },
+                    instance: Some(edge_uuid),
+                    direction,
+                    created_at,
+                });
+            }
+        }
+        edge_index
+    }
+
+    /// Adds `label` to a node; false if it already had it.
+    pub fn add_label(&mut self, idx: NodeIndex, label: &str) -> Result<bool, String> {
+        let node = self
+            .core
+            .node_weight_mut(idx)
+            .ok_or_else(|| format!("Node not found: {}", idx.index()))?;
+        if !node.labels.insert(label.to_string()) {
+            return Ok(false);
+        }
+        node.metadata.update();
+        self.label_index
+            .entry(label.to_string())
+            .or_default()
+            .insert(idx);
+        Ok(true)
+    }
+
+    pub fn remove_label(&mut self, idx: NodeIndex, label: &str) -> bool {
+        let Some(node) = self.core.node_weight_mut(idx) else {
+            return false;
+        };
+        if !node.labels.remove(label) {
+            return false;
+        }
+        node.metadata.update();
+        if let Some(nodes) = self.label_index.get_mut(label) {
+            nodes.remove(&idx);
+            if nodes.is_empty() {
+                self.label_index.remove(label);
+            }
+        }
+        true
+    }
+
+    pub fn has_label(&self, idx: NodeIndex, label: &str) -> bool {
+        self.core
+            .node_weight(idx)
+            .is_some_and(|n| n.labels.contains(label))
+    }
+
+    pub fn nodes_with_label(&self, label: &str) -> Vec<Ref<NodeIndex>> {
+        self.label_index
+            .get(label)
+            .into_iter()
+            .flatten()
+            .map(|&idx| self.node_ref(idx))
+            .collect()
+    }
+
+    /// Every label in use with the number of nodes carrying it.
+    pub fn labels(&self) -> Vec<(String, usize)> {
+        let mut v: Vec<_> = self
+            .label_index
+            .iter()
+            .map(|(label, nodes)| (label.clone(), nodes.len()))
+            .collect();
+        v.sort();
+        v
+    }
+
+    pub fn get_prop(&self, idx: NodeIndex, key: &str) -> Option<&NodeType> {
+        self.core.node_weight(idx)?.props.get(key)
+    }
+
+    /// Sets a property and returns the previous value.
+    pub fn set_prop(
+        &mut self,
+        idx: NodeIndex,
+        key: &str,
+        value: NodeType,
+    ) -> Result<Option<NodeType>, String> {
+        if self.core.node_weight(idx).is_none() {
+            return Err(format!("Node not found: {}", idx.index()));
+        }
+        self.unindex_node(idx);
+        let node = &mut self.core[idx];
+        let previous = node.props.insert(key.to_string(), value);
+        node.metadata.update();
+        self.index_node(idx);
+        Ok(previous)
+    }
+
+    pub fn remove_prop(&mut self, idx: NodeIndex, key: &str) -> Option<NodeType> {
+        if !self.core.node_weight(idx)?.props.contains_key(key) {
+            return None;
+        }
+        self.unindex_node(idx);
+        let node = &mut self.core[idx];
+        let removed = node.props.remove(key);
+        node.metadata.update();
+        self.index_node(idx);
+        removed
+    }
+
+    /// Replaces a node's content and returns the previous one. An embedding the
+    /// new content provides replaces the node's; one that came from the old
+    /// content is dropped with it.
+    pub fn set_content(&mut self, idx: NodeIndex, content: T) -> Result<T, String> {
+        if self.core.node_weight(idx).is_none() {
+            return Err(format!("Node not found: {}", idx.index()));
+        }
+        let embedding = content.embedding();
+        if let Some(embedding) = &embedding {
+            let name = self.core[idx].name.clone();
+            self.index_embedding(idx, &name, embedding)?;
+        }
+        self.unindex_node(idx);
+        self.text_index
+            .remove(idx, &search_document(&self.core[idx]));
+        let node = &mut self.core[idx];
+        let previous = std::mem::replace(&mut node.content, content);
+        node.metadata.update();
+        self.index_node(idx);
+        self.text_index
+            .insert(idx, &search_document(&self.core[idx]));
+        let derived = previous
+            .embedding()
+            .is_some_and(|old| self.core[idx].embedding.as_ref() == Some(&old));
+        match embedding {
+            Some(embedding) => self.core[idx].embedding = Some(embedding),
+            None if derived => {
+                self.core[idx].embedding = None;
+                self.rebuild_vector_index();
+            }
+            None => {}
+        }
+        Ok(previous)
+    }
+
+    // -----------SECONDARY INDEXES---------------------------------
+    /// Declares an index over node content or a property and fills it from the
+    /// current nodes.
+    pub fn create_index(
+        &mut self,
+        name: &str,
+        target: IndexTarget,
+        kind: IndexKind,
+    ) -> Result<(), String> {
+        if self.indexes.contains_key(name) {
+            return Err(format!("Index already exists: {}", name));
+        }
+        let mut index = SecondaryIndex::new(target, kind);
+        for idx in self.core.node_indices() {
+            if let Some(key) = index.key_of(&self.core[idx]) {
+                index.insert(key, idx);
+            }
+        }
+        self.indexes.insert(name.to_string(), index);
+        Ok(())
+    }
+
+    pub fn drop_index(&mut self, name: &str) -> bool {
+        self.indexes.remove(name).is_some()
+    }
+
+    pub fn index(&self, name: &str) -> Option<&SecondaryIndex> {
+        self.indexes.get(name)
+    }
+
+    pub fn index_names(&self) -> Vec<&str> {
+        self.indexes.keys().map(String::as_str).collect()
+    }
+
+    /// Nodes whose indexed value equals `value`.
+    pub fn lookup(&self, index: &str, value: &NodeType) -> Result<Vec<Ref<NodeIndex>>, String> {
+        let index = self.get_index(index)?;
+        Ok(self.node_refs(index.get(value)))
+    }
+
+    /// Nodes whose indexed value falls in `range`, e.g. `Num(10)..=Num(20)`.
+    /// Open ranges such as `Num(20)..` keep to numbers. Needs an ordered index.
+    pub fn lookup_range<R: RangeBounds<NodeType>>(
+        &self,
+        index: &str,
+        range: R,
+    ) -> Result<Vec<Ref<NodeIndex>>, String> {
+        let index = self.get_index(index)?;
+        Ok(self.node_refs(index.range(range)?))
+    }
+
+    /// Nodes whose indexed value is text starting with `prefix`. Needs an
+    /// ordered index.
+    pub fn lookup_prefix(&self, index: &str, prefix: &str) -> Result<Vec<Ref<NodeIndex>>, String> {
+        let index = self.get_index(index)?;
+        Ok(self.node_refs(index.prefix(prefix)?))
+    }
+
+    fn get_index(&self, name: &str) -> Result<&SecondaryIndex, String> {
+        self.indexes
+            .get(name)
+            .ok_or_else(|| format!("Index not found: {}", name))
+    }
+
+    fn node_refs(&self, nodes: Vec<NodeIndex>) -> Vec<Ref<NodeIndex>> {
+        nodes.into_iter().map(|idx| self.node_ref(idx)).collect()
+    }
+
+    fn index_node(&mut self, idx: NodeIndex) {
+        let node = &self.core[idx];
+        for index in self.indexes.values_mut() {
+            if let Some(key) = index.key_of(node) {
+                index.insert(key, idx);
+            }
+        }
+    }
+
+    fn unindex_node(&mut self, idx: NodeIndex) {
+        let node = &self.core[idx];
+        for index in self.indexes.values_mut() {
+            if let Some(key) = index.key_of(node) {
+                index.remove(&key, idx);
+            }
+        }
+    }
+
+    /// Copies index declarations from `other`; entries follow on the next
+    /// `rebuild_indexes`.
+    fn share_indexes(&mut self, other: &Self) {
+        self.indexes = other
+            .indexes
+            .iter()
+            .map(|(name, index)| {
+                (
+                    name.clone(),
+                    SecondaryIndex::new(index.target.clone(), index.kind),
+                )
+            })
+            .collect();
+    }
+
+    pub fn edge_data(&self, edge_uuid: Uuid) -> Option<&EdgeData> {
+        self.edge_data.get(&edge_uuid)
+    }
+
+    /// Instance data behind a connection; both endpoints resolve to the same entry.
+    pub fn connection_data(&self, connection: &Connection) -> Option<&EdgeData> {
+        self.edge_data.get(&connection.instance?)
+    }
+
+    /// Instance uuid of the `edge_kind_name` edge from `from_idx` to `to_idx`
+    /// (either way round in undirected graphs).
+    pub fn find_edge(
+        &self,
+        from_idx: NodeIndex,
+        to_idx: NodeIndex,
+        edge_kind_name: &str,
+    ) -> Option<Uuid> {
+        self.find_existing_connection(edge_kind_name, from_idx, to_idx)?
+            .instance
+    }
+
+    pub fn get_edge_prop(&self, edge_uuid: Uuid, key: &str) -> Option<&NodeType> {
+        self.edge_data.get(&edge_uuid)?.props.get(key)
+    }
+
+    /// Sets a property on one edge instance and returns the previous value.
+    pub fn set_edge_prop(
+        &mut self,
+        edge_uuid: Uuid,
+        key: &str,
+        value: NodeType,
+    ) -> Result<Option<NodeType>, String> {
+        let data = self
+            .edge_data
+            .get_mut(&edge_uuid)
+            .ok_or_else(|| format!("Edge not found: {}", edge_uuid))?;
+        let previous = data.props.insert(key.to_string(), value);
+        data.metadata.update();
+        Ok(previous)
+    }
+
+    pub fn remove_edge_prop(&mut self, edge_uuid: Uuid, key: &str) -> Option<NodeType> {
+        let data = self.edge_data.get_mut(&edge_uuid)?;
+        let removed = data.props.remove(key);
+        if removed.is_some() {
+            data.metadata.update();
+        }
+        removed
+    }
+
+    pub fn set_edge_description(
+        &mut self,
+        edge_uuid: Uuid,
+        description: &str,
+    ) -> Result<(), String> {
+        let data = self
+            .edge_data
+            .get_mut(&edge_uuid)
+            .ok_or_else(|| format!("Edge not found: {}", edge_uuid))?;
+        data.metadata.description = description.to_string();
+        data.metadata.update();
+        Ok(())
+    }
+
+    pub fn node_ref(&self, idx: NodeIndex) -> Ref<NodeIndex> {
+        Ref {
+            name: self.core[idx].name.clone(),
+            uuid: self.core[idx].metadata.id,
+            index: idx,
+        }
+    }
+
     pub fn load_from_file(path: &str) -> Result<Self, String> {
         let data = fs::read_to_string(path).map_err(|e| format!("Error to read file: {}", e))?;
         let mut g: Self = serde_json::from_str(&data)
             .map_err(|e| format!("Error to deserialize Graph: {}", e))?;
         g.rebuild_indexes();
+        g.restore_energy_policy();
         Ok(g)
     }
 
@@ -285,28 +988,104 @@ where
             std::fs::create_dir_all(parent)
                 .map_err(|e| format!("Error to create directories: {}", e))?;
         }
-        let mut clone = self.clone_for_save();
+        let clone = self.clone_for_save();
         let json = serde_json::to_string_pretty(&clone)
             .map_err(|e| format!("Error to serialize Graph: {}", e))?;
         std::fs::write(path, json).map_err(|e| format!("Error to write file: {}", e))
     }
 
     pub fn save_to_file_encrypted(&self, path: &str, passphrase: &str) -> Result<(), String> {
-        let mut clone = self.clone_for_save();
+        let clone = self.clone_for_save();
         crate::runtime::persist::save_encrypted(&clone, path, passphrase)
     }
 
     pub fn load_from_file_encrypted(path: &str, passphrase: &str) -> Result<Self, String> {
         let mut g: Self = crate::runtime::persist::load_encrypted(path, passphrase)?;
         g.rebuild_indexes();
+        g.restore_energy_policy();
         Ok(g)
     }
 
-    fn rebuild_indexes(&mut self) {
+    pub(crate) fn rebuild_indexes(&mut self) {
+        self.link_edge_data();
         self.node_index_by_name.clear();
+        self.label_index.clear();
+        for index in self.indexes.values_mut() {
+            index.clear();
+        }
         for idx in self.core.node_indices() {
+            self.index_node(idx);
             let name = self.core[idx].name.clone();
             self.node_index_by_name.insert(name, idx);
+            for label in &self.core[idx].labels {
+                self.label_index
+                    .entry(label.clone())
+                    .or_default()
+                    .insert(idx);
+            }
+        }
+        let core = &self.core;
+        let restored = self
+            .vector_index
+            .restore(|idx| core.node_weight(idx).and_then(|n| n.embedding.as_ref()));
+        let embedded = self
+            .core
+            .node_weights()
+            .filter(|n| n.embedding.is_some())
+            .count();
+        if !restored || embedded != self.vector_index.len() {
+            self.rebuild_vector_index();
+        }
+        self.restore_text_index();
+    }
+
+    // Files saved before edge instances had their own data: point connections
+    // at their instance and direction, restore missing ones and create the
+    // missing entries.
+    fn link_edge_data(&mut self) {
+        let refs: Vec<(Uuid, String, EdgeIndex)> = self
+            .runtime_ref
+            .edges
+            .iter()
+            .map(|r| (r.uuid, r.name.clone(), r.index))
+            .collect();
+        for (uuid, name, index) in refs {
+            let Some((source, target)) = self.core.edge_endpoints(index) else {
+                continue;
+            };
+            let mut created_at = None;
+            for (a, b, outgoing) in [(source, target, true), (target, source, false)] {
+                let direction = self.direction_at(outgoing);
+                if let Some(c) = self.core[a].connections.iter_mut().find(|c| {
+                    c.node.index == b
+                        && c.edge.name == name
+                        && c.instance.is_none_or(|i| i == uuid)
+                        && (c.direction == direction || c.direction == ConnectionDirection::Both)
+                }) {
+                    c.instance = Some(uuid);
+                    c.direction = direction;
+                    created_at = created_at.or(c.created_at);
+                } else if let Some(kind) = self.edge_kinds.iter().find(|k| k.name == name) {
+                    // older versions kept one connection per neighbour and dropped the rest
+                    let edge = Ref {
+                        name: name.clone(),
+                        uuid: kind.metadata.id,
+                        index: kind.metadata.id,
+                    };
+                    let node = self.node_ref(b);
+                    self.core[a].connections.push(Connection {
+                        node,
+                        edge,
+                        instance: Some(uuid),
+                        direction,
+                        created_at: None,
+                    });
+                }
+            }
+            let created_at = created_at.unwrap_or(self.metadata.created_at);
+            self.edge_data
+                .entry(uuid)
+                .or_insert_with(|| EdgeData::new(uuid, created_at));
         }
     }
 
@@ -318,7 +1097,16 @@ where
             runtime_ref: self.runtime_ref.clone(),
             metadata: self.metadata.clone(),
             edge_kinds: self.edge_kinds.clone(),
+            edge_data: self.edge_data.clone(),
+            energy_policy: Arc::clone(&self.energy_policy),
+            energy_spec: self.energy_spec,
+            energy_stale: self.energy_stale,
+            vector_index: self.vector_index.clone(),
+            text_index: self.text_index.clone(),
+            schema: self.schema.clone(),
+            indexes: self.indexes.clone(),
             node_index_by_name: HashMap::new(),
+            label_index: HashMap::new(),
         };
         cloned.runtime_ref.nodes.sort_by_key(|r| r.index.index());
         cloned.runtime_ref.edges.sort_by_key(|r| r.index.index());
@@ -327,45 +1115,259 @@ where
             .edge_kinds
             .sort_by(|a, b| a.name.cmp(&b.name));
         cloned.edge_kinds.sort_by(|a, b| a.name.cmp(&b.name));
+        cloned.refresh_energy();
         cloned
     }
 
+    pub fn set_energy_policy<P: EnergyPolicy<T, Ty> + 'static>(&mut self, policy: P) {
+        self.energy_spec = policy.spec();
+        self.energy_policy = Arc::new(policy);
+        self.recompute_energy();
+    }
+
+    pub fn energy_spec(&self) -> EnergySpec {
+        self.energy_spec
+    }
+
+    fn share_energy_policy(&mut self, other: &Self) {
+        self.energy_policy = Arc::clone(&other.energy_policy);
+        self.energy_spec = other.energy_spec;
+    }
+
+    // Custom policies are not persisted: fall back to degree and recompute so
+    // the loaded energies are never on a mix of scales.
+    fn restore_energy_policy(&mut self) {
+        match self.energy_spec.policy() {
+            Some(policy) => self.energy_policy = policy,
+            None => {
+                self.energy_policy = default_energy_policy();
+                self.energy_spec = EnergySpec::Degree;
+                self.recompute_energy();
+            }
+        }
+    }
+
+    /// Refreshes the energy of every node and edge kind with the current policy.
+    pub fn recompute_energy(&mut self) {
+        self.recompute_energy_at(Utc::now());
+    }
+
+    /// True when a global policy (centrality, `FnEnergy`) has seen mutations
+    /// that are not reflected in `Node::energy` yet.
+    pub fn energy_is_stale(&self) -> bool {
+        self.energy_stale
+    }
+
+    /// Recomputes energy if mutations were deferred. Global policies are not
+    /// rerun on every `add_connection`; call this once after a batch of inserts.
+    pub fn refresh_energy(&mut self) {
+        if self.energy_stale {
+            self.recompute_energy();
+        }
+    }
+
+    pub fn recompute_energy_at(&mut self, now: DateTime<Utc>) {
+        let energies = self.energy_policy.node_energies(self, now);
+        for (idx, energy) in self.core.node_indices().zip(energies) {
+            self.core[idx].energy = energy;
+        }
+        let kinds: Vec<f64> = self
+            .edge_kinds
+            .iter()
+            .map(|k| self.energy_policy.edge_kind_energy(self, k, now))
+            .collect();
+        for (kind, energy) in self.edge_kinds.iter_mut().zip(kinds) {
+            kind.energy = energy;
+        }
+        self.sort_runtime_nodes();
+        self.energy_stale = false;
+    }
+
+    pub fn record_access(&mut self, node_idx: NodeIndex) {
+        let node = &mut self.core[node_idx];
+        let now = Utc::now();
+        node.access_count = node.access_count.saturating_add(1);
+        node.last_accessed_at = Some(now);
+        node.recent_accesses.push_back(now);
+        if node.recent_accesses.len() > ACCESS_HISTORY {
+            node.recent_accesses.pop_front();
+        }
+        self.update_metrics_and_sort(node_idx, node_idx);
+    }
+
+    /// Highest-energy nodes. With deferred global changes pending, the policy is
+    /// evaluated on the fly instead of reading the stale stored energy.
     pub fn top_k_nodes_by_energy(&self, k: usize) -> Vec<Ref<NodeIndex>> {
-        let mut v: Vec<_> = self.runtime_ref.nodes.iter().cloned().collect();
-        v.sort_by(|a, b| self.core[b.index].energy.cmp(&self.core[a.index].energy));
+        if self.energy_stale {
+            return self.top_k_nodes_by_energy_at(k, Utc::now());
+        }
+        let mut v: Vec<_> = self.runtime_ref.nodes.to_vec();
+        v.sort_by(|a, b| {
+            self.core[b.index]
+                .energy
+                .total_cmp(&self.core[a.index].energy)
+        });
+        v.into_iter().take(k).collect()
+    }
+
+    /// Like `top_k_nodes_by_energy`, but evaluates the policy at `at` instead of
+    /// using the stored energy (which may have decayed since it was computed).
+    pub fn top_k_nodes_by_energy_at(&self, k: usize, at: DateTime<Utc>) -> Vec<Ref<NodeIndex>> {
+        let energies = self.energy_policy.node_energies(self, at);
+        let mut v: Vec<_> = self.runtime_ref.nodes.to_vec();
+        v.sort_by(|a, b| energies[b.index.index()].total_cmp(&energies[a.index.index()]));
         v.into_iter().take(k).collect()
     }
 
+    /// Nodes reached from `node_idx` over `edge_kind_name`, following edge direction.
     pub fn neighbors_by_edge_kind(
         &self,
         node_idx: NodeIndex,
         edge_kind_name: &str,
     ) -> Vec<Ref<NodeIndex>> {
-        self.core[node_idx]
-            .connections
-            .iter()
+        self.outgoing(node_idx)
+            .into_iter()
             .filter(|c| c.edge.name == edge_kind_name)
             .map(|c| c.node.clone())
             .collect()
     }
 
-    pub fn edge_kind_stats(&self) -> Vec<(String, u32)> {
+    /// Outgoing neighbours carrying `label`, optionally restricted to one edge kind.
+    pub fn neighbors_with_label(
+        &self,
+        node_idx: NodeIndex,
+        label: &str,
+        edge_kind_name: Option<&str>,
+    ) -> Vec<Ref<NodeIndex>> {
+        self.outgoing(node_idx)
+            .into_iter()
+            .filter(|c| edge_kind_name.is_none_or(|name| c.edge.name == name))
+            .filter(|c| self.core[c.node.index].labels.contains(label))
+            .map(|c| c.node.clone())
+            .collect()
+    }
+
+    /// Outgoing neighbours whose properties match `filter`, optionally restricted to one edge kind.
+    pub fn neighbors_where(
+        &self,
+        node_idx: NodeIndex,
+        edge_kind_name: Option<&str>,
+        filter: &PropFilter,
+    ) -> Vec<Ref<NodeIndex>> {
+        self.outgoing(node_idx)
+            .into_iter()
+            .filter(|c| edge_kind_name.is_none_or(|name| c.edge.name == name))
+            .filter(|c| filter.matches(&self.core[c.node.index].props))
+            .map(|c| c.node.clone())
+            .collect()
+    }
+
+    pub fn edge_kind_stats(&self) -> Vec<(String, f64)> {
         let mut v: Vec<_> = self
             .edge_kinds
             .iter()
             .map(|k| (k.name.clone(), k.energy))
             .collect();
-        v.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        v.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
         v
     }
 }
 
+// What the text index sees of a node.
+fn search_document<T: NodeTypes>(node: &Node<T>) -> String {
+    format!(
+        "{} {} {}",
+        node.name,
+        node.content.search_text(),
+        node.metadata.description
+    )
+}
+
+// The node's own embedding, or the one its content provides.
+fn node_embedding<T: NodeTypes>(node: &Node<T>) -> Option<Vec<f32>> {
+    node.embedding.clone().or_else(|| node.content.embedding())
+}
+
+/// Access timestamps kept per node for time-decayed energy.
+pub const ACCESS_HISTORY: usize = 64;
+
+fn default_energy_policy<T: NodeTypes, Ty: EdgeType>() -> Arc<dyn EnergyPolicy<T, Ty>> {
+    Arc::new(DegreeEnergy)
+}
+
 // -----------CONNECTIONS--------------------------
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Connection {
     pub node: Ref<NodeIndex>,
     // Reference to an edge kind (shared across many connections)
     pub edge: Ref<Uuid>,
+    // Edge instance in Grapho::edge_data, the same on both endpoints
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance: Option<Uuid>,
+    // Which end of the edge this node is; always Both in undirected graphs
+    #[serde(default)]
+    pub direction: ConnectionDirection,
+    // None for connections saved before timestamps were recorded
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ConnectionDirection {
+    #[default]
+    Both,
+    Outgoing,
+    Incoming,
+}
+
+/// Why `add_connection` refused an edge: a schema rule, or an embedding whose
+/// length does not match the vector index.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConnectionError {
+    Schema(SchemaError),
+    EmbeddingDimensions {
+        node: String,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for ConnectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConnectionError::Schema(e) => write!(f, "{}", e),
+            ConnectionError::EmbeddingDimensions {
+                node,
+                expected,
+                found,
+            } => write!(
+                f,
+                "node {}: expected {} embedding dimensions, got {}",
+                node, expected, found
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ConnectionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConnectionError::Schema(e) => Some(e),
+            ConnectionError::EmbeddingDimensions { .. } => None,
+        }
+    }
+}
+
+impl From<SchemaError> for ConnectionError {
+    fn from(e: SchemaError) -> Self {
+        ConnectionError::Schema(e)
+    }
+}
+
+impl From<ConnectionError> for String {
+    fn from(e: ConnectionError) -> Self {
+        format!("Error to add connection: {}", e)
+    }
 }
 // -----------REFS---------------------------------
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -386,10 +1388,22 @@ pub struct EdgeKindRef {
 pub struct Node<T> {
     pub name: String,
     pub content: T,
-    pub energy: u32,
+    pub energy: f64,
     pub connections: Vec<Connection>,
     pub node_index: NodeIndex,
     pub metadata: Metadata,
+    #[serde(default)]
+    pub access_count: u64,
+    #[serde(default)]
+    pub last_accessed_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "VecDeque::is_empty")]
+    pub recent_accesses: VecDeque<DateTime<Utc>>, // oldest first, at most ACCESS_HISTORY
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding: Option<Vec<f32>>,
+    #[serde(default, skip_serializing_if = "Props::is_empty")]
+    pub props: Props,
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub labels: BTreeSet<String>, // e.g. Person, Company
 }
 
 impl<T> Node<T> {
@@ -397,19 +1411,40 @@ impl<T> Node<T> {
         Self {
             name: String::from(name),
             content,
-            energy: 0,
+            energy: 0.0,
             connections: Vec::new(),
             node_index: NodeIndex::new(0),
             metadata: Metadata::new(description.to_string()),
+            access_count: 0,
+            last_accessed_at: None,
+            recent_accesses: VecDeque::new(),
+            embedding: None,
+            props: Props::new(),
+            labels: BTreeSet::new(),
         }
     }
+
+    pub fn with_embedding(mut self, embedding: Vec<f32>) -> Self {
+        self.embedding = Some(embedding);
+        self
+    }
+
+    pub fn with_label(mut self, label: &str) -> Self {
+        self.labels.insert(label.to_string());
+        self
+    }
+
+    pub fn with_prop(mut self, key: &str, value: NodeType) -> Self {
+        self.props.insert(key.to_string(), value);
+        self
+    }
 }
 
 // -----------EDGE KIND (reusable)---------------------------------
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EdgeKind {
     pub name: String,
-    pub energy: u32,
+    pub energy: f64,
     pub metadata: Metadata,
 }
 
@@ -417,12 +1452,44 @@ impl EdgeKind {
     fn new(name: &str, description: &str) -> Self {
         Self {
             name: name.to_string(),
-            energy: 0,
+            energy: 0.0,
             metadata: Metadata::new(description.to_string()),
         }
     }
 }
 
+// -----------EDGE INSTANCE---------------------------------
+/// Data owned by one edge; `metadata.id` is the instance uuid from `runtime_ref.edges`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EdgeData {
+    pub metadata: Metadata,
+    #[serde(default, skip_serializing_if = "Props::is_empty")]
+    pub props: Props,
+}
+
+impl EdgeData {
+    fn new(id: Uuid, created_at: DateTime<Utc>) -> Self {
+        let mut metadata = Metadata::new(String::new());
+        metadata.id = id;
+        metadata.created_at = created_at;
+        metadata.updated_at = created_at;
+        Self {
+            metadata,
+            props: Props::new(),
+        }
+    }
+}
+
+// Borrowed view of one edge instance, used to copy edges between graphs.
+pub(crate) struct EdgeInstance<'a> {
+    pub(crate) from: Uuid,
+    pub(crate) to: Uuid,
+    pub(crate) weight: u32,
+    pub(crate) kind: &'a EdgeKind,
+    pub(crate) created_at: Option<DateTime<Utc>>,
+    pub(crate) data: Option<&'a EdgeData>,
+}
+
 // -----------METADATA---------------------------------
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Metadata {
@@ -430,6 +1497,8 @@ pub struct Metadata {
     pub description: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub community: Option<usize>, // set by community detection on nodes
 }
 
 impl Metadata {
@@ -441,10 +1510,11 @@ impl Metadata {
             updated_at: now,
             id: Uuid::new_v4(),
             description,
+            community: None,
         }
     }
 
-    // pub fn update(&mut self) {
-    //     self.updated_at = Utc::now()
-    // }
+    pub fn update(&mut self) {
+        self.updated_at = Utc::now()
+    }
 }
@@ -1,2 +1,8 @@
+pub mod energy;
 pub mod graph_models;
 pub mod node_types;
+pub mod props;
+pub mod schema;
+pub mod secondary_index;
+pub mod text_index;
+pub mod vector_index;
@@ -1,6 +1,61 @@
+use chrono::{DateTime, Utc};
+use petgraph::graph::NodeIndex;
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use uuid::Uuid;
 
-pub trait NodeTypes {} // market trait for node types (just to crate some "inheritance" to include the generics type for Grapho struct)
+use crate::models::graph_models::Ref;
+
+/// Content stored in `Node::content`. Every hook has a default, so any type can
+/// be plugged into `Grapho<T, Ty>`; override the ones the type can answer.
+pub trait NodeTypes {
+    /// The content as a `NodeType`, when it is one; schema shape checks skip other types.
+    fn as_node_type(&self) -> Option<&NodeType> {
+        None
+    }
+
+    /// Words for full-text search; empty when the content has nothing to search.
+    fn search_text(&self) -> String {
+        String::new()
+    }
+
+    /// Value used by secondary indexes and ordering; `None` keeps the node out of them.
+    fn index_key(&self) -> Option<NodeType> {
+        self.as_node_type().cloned()
+    }
+
+    /// Vector used for the node when `Node::embedding` is not set.
+    fn embedding(&self) -> Option<Vec<f32>> {
+        None
+    }
+
+    /// Rough number of bytes held by the content, for stats and export budgets.
+    fn size_estimate(&self) -> usize {
+        std::mem::size_of_val(self)
+    }
+
+    /// Short human-readable form for reports and exports.
+    fn display_text(&self) -> String {
+        self.search_text()
+    }
+}
+
+impl NodeTypes for String {
+    fn search_text(&self) -> String {
+        self.clone()
+    }
+
+    fn index_key(&self) -> Option<NodeType> {
+        Some(NodeType::Text(self.clone()))
+    }
+
+    fn size_estimate(&self) -> usize {
+        self.len()
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum NodeType {
@@ -10,6 +65,211 @@ pub enum NodeType {
     Fnum64(f64),
     Bool(bool),
     Seq(Vec<NodeType>),
+    Map(BTreeMap<String, NodeType>),
+    Bytes(Vec<u8>),
+    DateTime(DateTime<Utc>),
+    Uuid(Uuid),
+    Null,
+    Ref(Ref<NodeIndex>), // pointer to another node, compared by uuid
+}
+
+impl NodeTypes for NodeType {
+    fn as_node_type(&self) -> Option<&NodeType> {
+        Some(self)
+    }
+
+    // Text values only, including the ones nested in Seq and Map.
+    fn search_text(&self) -> String {
+        match self {
+            NodeType::Text(s) => s.clone(),
+            NodeType::Seq(items) => join_text(items.iter()),
+            NodeType::Map(map) => join_text(map.values()),
+            _ => String::new(),
+        }
+    }
+
+    fn size_estimate(&self) -> usize {
+        let own = std::mem::size_of::<NodeType>();
+        match self {
+            NodeType::Text(s) => own + s.len(),
+            NodeType::Bytes(bytes) => own + bytes.len(),
+            NodeType::Seq(items) => own + items.iter().map(|v| v.size_estimate()).sum::<usize>(),
+            NodeType::Map(map) => {
+                own + map
+                    .iter()
+                    .map(|(k, v)| k.len() + v.size_estimate())
+                    .sum::<usize>()
+            }
+            NodeType::Ref(r) => own + r.name.len(),
+            _ => own,
+        }
+    }
+
+    fn display_text(&self) -> String {
+        self.to_string()
+    }
+}
+
+fn join_text<'a, I: Iterator<Item = &'a NodeType>>(values: I) -> String {
+    values
+        .map(|v| v.search_text())
+        .filter(|t| !t.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
 }
 
-impl NodeTypes for NodeType {}
+impl fmt::Display for NodeType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NodeType::Text(s) => write!(f, "{}", s),
+            NodeType::Num(n) => write!(f, "{}", n),
+            NodeType::Fnum32(n) => write!(f, "{}", n),
+            NodeType::Fnum64(n) => write!(f, "{}", n),
+            NodeType::Bool(b) => write!(f, "{}", b),
+            NodeType::Seq(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            NodeType::Map(map) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in map.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", key, value)?;
+                }
+                write!(f, "}}")
+            }
+            NodeType::Bytes(bytes) => write!(f, "<{} bytes>", bytes.len()),
+            NodeType::DateTime(at) => write!(f, "{}", at.to_rfc3339()),
+            NodeType::Uuid(id) => write!(f, "{}", id),
+            NodeType::Null => write!(f, "null"),
+            NodeType::Ref(r) => write!(f, "@{}", r.name),
+        }
+    }
+}
+
+impl NodeType {
+    // Position of the variant in the cross-type ordering; numbers share one rank.
+    pub(crate) fn rank(&self) -> u8 {
+        match self {
+            NodeType::Null => 0,
+            NodeType::Bool(_) => 1,
+            NodeType::Num(_) | NodeType::Fnum32(_) | NodeType::Fnum64(_) => 2,
+            NodeType::Text(_) => 3,
+            NodeType::Bytes(_) => 4,
+            NodeType::DateTime(_) => 5,
+            NodeType::Uuid(_) => 6,
+            NodeType::Ref(_) => 7,
+            NodeType::Seq(_) => 8,
+            NodeType::Map(_) => 9,
+        }
+    }
+
+    /// Numeric value with -0.0 folded into 0.0, so integers and floats compare by value.
+    pub fn as_f64(&self) -> Option<f64> {
+        let v = match self {
+            NodeType::Num(n) => *n as f64,
+            NodeType::Fnum32(f) => *f as f64,
+            NodeType::Fnum64(f) => *f,
+            _ => return None,
+        };
+        Some(if v == 0.0 { 0.0 } else { v })
+    }
+}
+
+// Integers and floats compare by exact value. Converting the integer to f64
+// would round large values, so that Num(MAX) and Num(MAX - 1) both equal the
+// same float without being equal to each other.
+fn cmp_numbers(a: &NodeType, b: &NodeType) -> Option<Ordering> {
+    match (a, b) {
+        (NodeType::Num(x), NodeType::Num(y)) => Some(x.cmp(y)),
+        (NodeType::Num(x), _) => Some(cmp_int_float(*x, b.as_f64()?)),
+        (_, NodeType::Num(y)) => Some(cmp_int_float(*y, a.as_f64()?).reverse()),
+        _ => Some(a.as_f64()?.total_cmp(&b.as_f64()?)),
+    }
+}
+
+// Places NaN where f64::total_cmp does: above (or, when negative, below) everything.
+fn cmp_int_float(i: isize, f: f64) -> Ordering {
+    const TWO_POW_63: f64 = 9_223_372_036_854_775_808.0;
+    if f.is_nan() {
+        return if f.is_sign_negative() {
+            Ordering::Greater
+        } else {
+            Ordering::Less
+        };
+    }
+    if f >= TWO_POW_63 {
+        return Ordering::Less;
+    }
+    if f < -TWO_POW_63 {
+        return Ordering::Greater;
+    }
+    // f is within i64 range here, so its integer part converts exactly
+    let whole = f.trunc();
+    match (i as i128).cmp(&(whole as i128)) {
+        Ordering::Equal => 0.0_f64.partial_cmp(&(f - whole)).unwrap_or(Ordering::Equal),
+        unequal => unequal,
+    }
+}
+
+// Values of different variants are ordered Null < Bool < numbers < Text < Bytes
+// < DateTime < Uuid < Ref < Seq < Map. Integers and floats compare numerically.
+impl Ord for NodeType {
+    fn cmp(&self, other: &Self) -> Ordering {
+        use NodeType::*;
+        match (self, other) {
+            (Bool(a), Bool(b)) => a.cmp(b),
+            (Text(a), Text(b)) => a.cmp(b),
+            (Bytes(a), Bytes(b)) => a.cmp(b),
+            (DateTime(a), DateTime(b)) => a.cmp(b),
+            (Uuid(a), Uuid(b)) => a.cmp(b),
+            (Ref(a), Ref(b)) => a.uuid.cmp(&b.uuid),
+            (Seq(a), Seq(b)) => a.cmp(b),
+            (Map(a), Map(b)) => a.cmp(b),
+            (Null, Null) => Ordering::Equal,
+            _ => cmp_numbers(self, other).unwrap_or_else(|| self.rank().cmp(&other.rank())),
+        }
+    }
+}
+
+impl PartialOrd for NodeType {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for NodeType {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for NodeType {}
+
+impl Hash for NodeType {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.rank().hash(state);
+        match self {
+            NodeType::Num(_) | NodeType::Fnum32(_) | NodeType::Fnum64(_) => {
+                self.as_f64().map(f64::to_bits).hash(state)
+            }
+            NodeType::Text(s) => s.hash(state),
+            NodeType::Bool(b) => b.hash(state),
+            NodeType::Seq(items) => items.hash(state),
+            NodeType::Map(map) => map.hash(state),
+            NodeType::Bytes(bytes) => bytes.hash(state),
+            NodeType::DateTime(at) => at.hash(state),
+            NodeType::Uuid(id) => id.hash(state),
+            NodeType::Null => {}
+            NodeType::Ref(r) => r.uuid.hash(state),
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::node_types::NodeType;
+
+/// Named values attached to a node, independent of its `content`.
+pub type Props = BTreeMap<String, NodeType>;
+
+/// Predicate over a property map. Comparisons use the `NodeType` ordering, so
+/// `Num(2)` and `Fnum64(2.0)` are equal; a missing key never matches except
+/// under `Not`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PropFilter {
+    Exists(String),
+    Eq(String, NodeType),
+    Ne(String, NodeType),
+    Lt(String, NodeType),
+    Le(String, NodeType),
+    Gt(String, NodeType),
+    Ge(String, NodeType),
+    /// Inclusive on both ends.
+    Between(String, NodeType, NodeType),
+    And(Vec<PropFilter>),
+    Or(Vec<PropFilter>),
+    Not(Box<PropFilter>),
+}
+
+impl PropFilter {
+    pub fn matches(&self, props: &Props) -> bool {
+        match self {
+            PropFilter::Exists(key) => props.contains_key(key),
+            PropFilter::Eq(key, v) => props.get(key).is_some_and(|p| p == v),
+            PropFilter::Ne(key, v) => props.get(key).is_some_and(|p| p != v),
+            PropFilter::Lt(key, v) => props.get(key).is_some_and(|p| p < v),
+            PropFilter::Le(key, v) => props.get(key).is_some_and(|p| p <= v),
+            PropFilter::Gt(key, v) => props.get(key).is_some_and(|p| p > v),
+            PropFilter::Ge(key, v) => props.get(key).is_some_and(|p| p >= v),
+            PropFilter::Between(key, low, high) => {
+                props.get(key).is_some_and(|p| low <= p && p <= high)
+            }
+            PropFilter::And(filters) => filters.iter().all(|f| f.matches(props)),
+            PropFilter::Or(filters) => filters.iter().any(|f| f.matches(props)),
+            PropFilter::Not(filter) => !filter.matches(props),
+        }
+    }
+}
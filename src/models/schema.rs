@@ -0,0 +1,474 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use petgraph::EdgeType;
+use petgraph::graph::NodeIndex;
+use serde::{Deserialize, Serialize};
+
+use crate::models::graph_models::{Grapho, Node};
+use crate::models::node_types::{NodeType, NodeTypes};
+
+/// Coarse type of a `NodeType` value, used to describe what a schema accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NodeShape {
+    Any,
+    Text,
+    Int,
+    Float,
+    Number, // Int or Float
+    Bool,
+    Seq,
+    Map,
+    Bytes,
+    DateTime,
+    Uuid,
+    Null,
+    Ref,
+}
+
+impl NodeShape {
+    pub fn of(value: &NodeType) -> Self {
+        match value {
+            NodeType::Text(_) => NodeShape::Text,
+            NodeType::Num(_) => NodeShape::Int,
+            NodeType::Fnum32(_) | NodeType::Fnum64(_) => NodeShape::Float,
+            NodeType::Bool(_) => NodeShape::Bool,
+            NodeType::Seq(_) => NodeShape::Seq,
+            NodeType::Map(_) => NodeShape::Map,
+            NodeType::Bytes(_) => NodeShape::Bytes,
+            NodeType::DateTime(_) => NodeShape::DateTime,
+            NodeType::Uuid(_) => NodeShape::Uuid,
+            NodeType::Null => NodeShape::Null,
+            NodeType::Ref(_) => NodeShape::Ref,
+        }
+    }
+
+    pub fn matches(&self, value: &NodeType) -> bool {
+        match (self, NodeShape::of(value)) {
+            (NodeShape::Any, _) => true,
+            (NodeShape::Number, NodeShape::Int | NodeShape::Float) => true,
+            (expected, found) => *expected == found,
+        }
+    }
+}
+
+/// What a node carrying a given label must look like.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LabelRule {
+    #[serde(default)]
+    pub content: Vec<NodeShape>, // accepted content shapes; empty accepts anything
+    #[serde(default)]
+    pub required: BTreeMap<String, NodeShape>, // properties that must be present
+}
+
+impl LabelRule {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_content(mut self, shape: NodeShape) -> Self {
+        self.content.push(shape);
+        self
+    }
+
+    pub fn with_required(mut self, key: &str, shape: NodeShape) -> Self {
+        self.required.insert(key.to_string(), shape);
+        self
+    }
+}
+
+/// Which labels an edge kind may connect and how many edges of that kind a node may have.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EdgeRule {
+    #[serde(default)]
+    pub from: Option<String>, // required label on the source; None accepts any node
+    #[serde(default)]
+    pub to: Option<String>,
+    #[serde(default)]
+    pub max_out: Option<usize>, // edges of this kind per source node
+    #[serde(default)]
+    pub max_in: Option<usize>, // edges of this kind per target node
+}
+
+impl EdgeRule {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// e.g. `EdgeRule::between("Person", "Company")` for `works_at`.
+    pub fn between(from: &str, to: &str) -> Self {
+        Self {
+            from: Some(from.to_string()),
+            to: Some(to.to_string()),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_max_out(mut self, limit: usize) -> Self {
+        self.max_out = Some(limit);
+        self
+    }
+
+    pub fn with_max_in(mut self, limit: usize) -> Self {
+        self.max_in = Some(limit);
+        self
+    }
+
+    fn accepts<T>(&self, from: &Node<T>, to: &Node<T>) -> bool {
+        let has = |node: &Node<T>, label: &Option<String>| {
+            label.as_ref().is_none_or(|l| node.labels.contains(l))
+        };
+        has(from, &self.from) && has(to, &self.to)
+    }
+}
+
+// -----------SCHEMA---------------------------------
+/// Optional constraints checked by `Grapho::add_connection` and `Grapho::validate`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Schema {
+    #[serde(default)]
+    pub labels: BTreeMap<String, LabelRule>, // labels not listed here are rejected
+    #[serde(default)]
+    pub edge_kinds: BTreeMap<String, EdgeRule>,
+    #[serde(default = "allow")]
+    pub allow_unlabeled: bool,
+    #[serde(default = "allow")]
+    pub allow_other_edge_kinds: bool, // edge kinds missing from `edge_kinds`
+}
+
+// Missing allow flags default to true, as in `Schema::new`.
+fn allow() -> bool {
+    true
+}
+
+impl Default for Schema {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Schema {
+    pub fn new() -> Self {
+        Self {
+            labels: BTreeMap::new(),
+            edge_kinds: BTreeMap::new(),
+            allow_unlabeled: true,
+            allow_other_edge_kinds: true,
+        }
+    }
+
+    pub fn with_label(mut self, label: &str, rule: LabelRule) -> Self {
+        self.labels.insert(label.to_string(), rule);
+        self
+    }
+
+    pub fn with_edge_kind(mut self, name: &str, rule: EdgeRule) -> Self {
+        self.edge_kinds.insert(name.to_string(), rule);
+        self
+    }
+
+    pub fn deny_unlabeled(mut self) -> Self {
+        self.allow_unlabeled = false;
+        self
+    }
+
+    pub fn deny_other_edge_kinds(mut self) -> Self {
+        self.allow_other_edge_kinds = false;
+        self
+    }
+
+    /// Every rule `node` breaks on its own (labels, content and properties).
+    pub fn node_violations<T: NodeTypes>(&self, node: &Node<T>) -> Vec<SchemaError> {
+        let mut errors = vec![];
+        if node.labels.is_empty() && !self.allow_unlabeled {
+            errors.push(SchemaError::MissingLabel {
+                node: node.name.clone(),
+            });
+        }
+        for label in &node.labels {
+            let Some(rule) = self.labels.get(label) else {
+                errors.push(SchemaError::LabelNotAllowed {
+                    node: node.name.clone(),
+                    label: label.clone(),
+                });
+                continue;
+            };
+            if let Some(content) = node.content.as_node_type()
+                && !rule.content.is_empty()
+                && !rule.content.iter().any(|s| s.matches(content))
+            {
+                errors.push(SchemaError::ContentShape {
+                    node: node.name.clone(),
+                    label: label.clone(),
+                    found: NodeShape::of(content),
+                });
+            }
+            for (key, shape) in &rule.required {
+                match node.props.get(key) {
+                    None => errors.push(SchemaError::MissingProperty {
+                        node: node.name.clone(),
+                        label: label.clone(),
+                        key: key.clone(),
+                    }),
+                    Some(value) if !shape.matches(value) => {
+                        errors.push(SchemaError::PropertyShape {
+                            node: node.name.clone(),
+                            key: key.clone(),
+                            expected: *shape,
+                            found: NodeShape::of(value),
+                        })
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+        errors
+    }
+
+    /// Checks the kind and endpoint labels of one edge; undirected edges may
+    /// match the rule either way round.
+    pub fn check_edge<T>(
+        &self,
+        edge_kind: &str,
+        from: &Node<T>,
+        to: &Node<T>,
+        directed: bool,
+    ) -> Result<(), SchemaError> {
+        let Some(rule) = self.edge_kinds.get(edge_kind) else {
+            if self.allow_other_edge_kinds {
+                return Ok(());
+            }
+            return Err(SchemaError::EdgeKindNotAllowed {
+                edge_kind: edge_kind.to_string(),
+            });
+        };
+        if rule.accepts(from, to) || (!directed && rule.accepts(to, from)) {
+            Ok(())
+        } else {
+            Err(SchemaError::EndpointLabels {
+                edge_kind: edge_kind.to_string(),
+                from: from.name.clone(),
+                to: to.name.clone(),
+            })
+        }
+    }
+}
+
+// -----------ERRORS---------------------------------
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemaError {
+    MissingLabel {
+        node: String,
+    },
+    LabelNotAllowed {
+        node: String,
+        label: String,
+    },
+    ContentShape {
+        node: String,
+        label: String,
+        found: NodeShape,
+    },
+    MissingProperty {
+        node: String,
+        label: String,
+        key: String,
+    },
+    PropertyShape {
+        node: String,
+        key: String,
+        expected: NodeShape,
+        found: NodeShape,
+    },
+    EdgeKindNotAllowed {
+        edge_kind: String,
+    },
+    EndpointLabels {
+        edge_kind: String,
+        from: String,
+        to: String,
+    },
+    Cardinality {
+        edge_kind: String,
+        node: String,
+        limit: usize,
+    },
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaError::MissingLabel { node } => write!(f, "node {} has no label", node),
+            SchemaError::LabelNotAllowed { node, label } => {
+                write!(f, "node {}: label {} is not allowed", node, label)
+            }
+            SchemaError::ContentShape { node, label, found } => {
+                write!(
+                    f,
+                    "node {}: {:?} content is not allowed for {}",
+                    node, found, label
+                )
+            }
+            SchemaError::MissingProperty { node, label, key } => {
+                write!(f, "node {}: {} requires property {}", node, label, key)
+            }
+            SchemaError::PropertyShape {
+                node,
+                key,
+                expected,
+                found,
+            } => write!(
+                f,
+                "node {}: property {} must be {:?}, found {:?}",
+                node, key, expected, found
+            ),
+            SchemaError::EdgeKindNotAllowed { edge_kind } => {
+                write!(f, "edge kind {} is not allowed", edge_kind)
+            }
+            SchemaError::EndpointLabels {
+                edge_kind,
+                from,
+                to,
+            } => write!(f, "{} cannot connect {} to {}", edge_kind, from, to),
+            SchemaError::Cardinality {
+                edge_kind,
+                node,
+                limit,
+            } => write!(
+                f,
+                "node {} would exceed {} {} connections",
+                node, limit, edge_kind
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+impl From<SchemaError> for String {
+    fn from(e: SchemaError) -> Self {
+        format!("Error to add connection: {}", e)
+    }
+}
+
+impl<T: NodeTypes, Ty: EdgeType> Grapho<T, Ty>
+where
+    T: Clone + Serialize + for<'de> Deserialize<'de>,
+    Ty: EdgeType + Serialize + for<'de> Deserialize<'de>,
+{
+    /// Checks every node, edge and cardinality limit against the schema and
+    /// returns all violations; a graph without a schema is always valid.
+    pub fn validate(&self) -> Result<(), Vec<SchemaError>> {
+        let Some(schema) = self.schema() else {
+            return Ok(());
+        };
+        let mut errors: Vec<SchemaError> = self
+            .core
+            .node_weights()
+            .flat_map(|node| schema.node_violations(node))
+            .collect();
+        let directed = self.core.is_directed();
+        for r in &self.runtime_ref.edges {
+            if let Some((a, b)) = self.core.edge_endpoints(r.index)
+                && let Err(e) = schema.check_edge(&r.name, &self.core[a], &self.core[b], directed)
+            {
+                errors.push(e);
+            }
+        }
+        for (kind, rule) in &schema.edge_kinds {
+            for idx in self.core.node_indices() {
+                let (out, inc) = self.kind_degree(idx, kind);
+                for (count, limit) in [(out, rule.max_out), (inc, rule.max_in)] {
+                    if let Some(limit) = limit.filter(|&l| count > l) {
+                        errors.push(SchemaError::Cardinality {
+                            edge_kind: kind.clone(),
+                            node: self.core[idx].name.clone(),
+                            limit,
+                        });
+                    }
+                }
+            }
+        }
+        errors.dedup();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Schema check for a connection about to be added; nodes already in the
+    /// graph are checked as stored, since `add_connection` keeps them as they are.
+    pub(crate) fn check_connection(
+        &self,
+        from: &Node<T>,
+        to: &Node<T>,
+        edge_kind: &str,
+        new_edge: bool,
+    ) -> Result<(), SchemaError> {
+        let Some(schema) = self.schema() else {
+            return Ok(());
+        };
+        let stored = |node: &Node<T>| self.get_node_index_by_name(&node.name);
+        let (from_idx, to_idx) = (stored(from), stored(to));
+        let from = from_idx.map_or(from, |idx| &self.core[idx]);
+        let to = to_idx.map_or(to, |idx| &self.core[idx]);
+        for node in [from, to] {
+            if let Some(e) = schema.node_violations(node).into_iter().next() {
+                return Err(e);
+            }
+        }
+        schema.check_edge(edge_kind, from, to, self.core.is_directed())?;
+        let Some(rule) = schema.edge_kinds.get(edge_kind).filter(|_| new_edge) else {
+            return Ok(());
+        };
+        let checks = [
+            (from_idx, from, rule.max_out, true),
+            (to_idx, to, rule.max_in, false),
+        ];
+        for (idx, node, limit, outgoing) in checks {
+            let Some(limit) = limit else {
+                continue;
+            };
+            let (out, inc) = idx.map_or((0, 0), |idx| self.kind_degree(idx, edge_kind));
+            if (if outgoing { out } else { inc }) + 1 > limit {
+                return Err(SchemaError::Cardinality {
+                    edge_kind: edge_kind.to_string(),
+                    node: node.name.clone(),
+                    limit,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    // (outgoing, incoming) edges of `edge_kind` at `node`; both are the plain
+    // degree in undirected graphs.
+    fn kind_degree(&self, node: NodeIndex, edge_kind: &str) -> (usize, usize) {
+        let (mut out, mut inc) = (0, 0);
+        for r in self
+            .runtime_ref
+            .edges
+            .iter()
+            .filter(|r| r.name == edge_kind)
+        {
+            let Some((a, b)) = self.core.edge_endpoints(r.index) else {
+                continue;
+            };
+            if !self.core.is_directed() {
+                if a == node || b == node {
+                    out += 1;
+                    inc += 1;
+                }
+                continue;
+            }
+            if a == node {
+                out += 1;
+            }
+            if b == node {
+                inc += 1;
+            }
+        }
+        (out, inc)
+    }
+}
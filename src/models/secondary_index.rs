@@ -0,0 +1,145 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::ops::{Bound, RangeBounds};
+
+use petgraph::graph::NodeIndex;
+use serde::{Deserialize, Serialize};
+
+use crate::models::graph_models::Node;
+use crate::models::node_types::{NodeType, NodeTypes};
+
+/// What a secondary index reads from each node.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IndexTarget {
+    Content,      // NodeTypes::index_key of the content
+    Prop(String), // one entry of Node::props
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IndexKind {
+    Hash,    // equality only
+    Ordered, // equality, ranges and Text prefixes
+}
+
+// -----------SECONDARY INDEX---------------------------------
+/// Value -> nodes map over node content or a property. Only the declaration is
+/// persisted; entries are rebuilt from the nodes on load.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecondaryIndex {
+    pub target: IndexTarget,
+    pub kind: IndexKind,
+    #[serde(skip)]
+    hash: HashMap<NodeType, BTreeSet<NodeIndex>>,
+    #[serde(skip)]
+    ordered: BTreeMap<NodeType, BTreeSet<NodeIndex>>,
+}
+
+impl SecondaryIndex {
+    pub fn new(target: IndexTarget, kind: IndexKind) -> Self {
+        Self {
+            target,
+            kind,
+            hash: HashMap::new(),
+            ordered: BTreeMap::new(),
+        }
+    }
+
+    /// The value this index stores for `node`, if any.
+    pub fn key_of<T: NodeTypes>(&self, node: &Node<T>) -> Option<NodeType> {
+        match &self.target {
+            IndexTarget::Content => node.content.index_key(),
+            IndexTarget::Prop(key) => node.props.get(key).cloned(),
+        }
+    }
+
+    pub fn insert(&mut self, key: NodeType, node: NodeIndex) {
+        match self.kind {
+            IndexKind::Hash => self.hash.entry(key).or_default().insert(node),
+            IndexKind::Ordered => self.ordered.entry(key).or_default().insert(node),
+        };
+    }
+
+    pub fn remove(&mut self, key: &NodeType, node: NodeIndex) {
+        let nodes = match self.kind {
+            IndexKind::Hash => self.hash.get_mut(key),
+            IndexKind::Ordered => self.ordered.get_mut(key),
+        };
+        let Some(nodes) = nodes else {
+            return;
+        };
+        nodes.remove(&node);
+        if nodes.is_empty() {
+            self.hash.remove(key);
+            self.ordered.remove(key);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.hash.clear();
+        self.ordered.clear();
+    }
+
+    /// Nodes whose value equals `key` (so `Num(2)` also finds `Fnum64(2.0)`).
+    pub fn get(&self, key: &NodeType) -> Vec<NodeIndex> {
+        let nodes = match self.kind {
+            IndexKind::Hash => self.hash.get(key),
+            IndexKind::Ordered => self.ordered.get(key),
+        };
+        nodes.into_iter().flatten().copied().collect()
+    }
+
+    /// Nodes whose value falls in `range`, in value order. An open end stays
+    /// within the type of the other bound, so `Num(20)..` matches numbers only
+    /// and not the `Text` values that sort above them. Hash indexes cannot
+    /// answer range queries.
+    pub fn range<R: RangeBounds<NodeType>>(&self, range: R) -> Result<Vec<NodeIndex>, String> {
+        self.require_ordered()?;
+        if is_empty_range(&range) {
+            return Ok(vec![]);
+        }
+        let rank = |bound: Bound<&NodeType>| match bound {
+            Bound::Included(key) | Bound::Excluded(key) => Some(key.rank()),
+            Bound::Unbounded => None,
+        };
+        let (start, end) = (rank(range.start_bound()), rank(range.end_bound()));
+        let (lowest, highest) = (start.or(end), end.or(start));
+        let bounds = (range.start_bound().cloned(), range.end_bound().cloned());
+        Ok(self
+            .ordered
+            .range(bounds)
+            .skip_while(|(key, _)| lowest.is_some_and(|r| key.rank() < r))
+            .take_while(|(key, _)| highest.is_none_or(|r| key.rank() <= r))
+            .flat_map(|(_, nodes)| nodes.iter().copied())
+            .collect())
+    }
+
+    /// Nodes whose value is a `Text` starting with `prefix`, in value order.
+    pub fn prefix(&self, prefix: &str) -> Result<Vec<NodeIndex>, String> {
+        self.require_ordered()?;
+        let start = NodeType::Text(prefix.to_string());
+        Ok(self
+            .ordered
+            .range((Bound::Included(start), Bound::Unbounded))
+            .take_while(|(key, _)| matches!(key, NodeType::Text(t) if t.starts_with(prefix)))
+            .flat_map(|(_, nodes)| nodes.iter().copied())
+            .collect())
+    }
+
+    fn require_ordered(&self) -> Result<(), String> {
+        match self.kind {
+            IndexKind::Ordered => Ok(()),
+            IndexKind::Hash => {
+                Err("Error to query index: hash indexes only support equality".into())
+            }
+        }
+    }
+}
+
+// BTreeMap::range panics on inverted bounds; treat them as matching nothing.
+fn is_empty_range<R: RangeBounds<NodeType>>(range: &R) -> bool {
+    match (range.start_bound(), range.end_bound()) {
+        (Bound::Included(a), Bound::Included(b)) => a > b,
+        (Bound::Included(a) | Bound::Excluded(a), Bound::Excluded(b))
+        | (Bound::Excluded(a), Bound::Included(b)) => a >= b,
+        _ => false,
+    }
+}
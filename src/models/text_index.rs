@@ -0,0 +1,141 @@
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Bound;
+
+use petgraph::graph::NodeIndex;
+use serde::{Deserialize, Serialize};
+
+const BM25_K1: f32 = 1.2;
+const BM25_B: f32 = 0.75;
+const PREFIX_WEIGHT: f32 = 0.5; // a prefix hit counts half as much as a whole word
+
+/// Lowercased alphanumeric runs; everything else separates tokens.
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+// FNV-1a, stable across builds so persisted fingerprints stay comparable.
+fn fingerprint(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+// -----------INVERTED INDEX---------------------------------
+/// Token -> nodes map scored with BM25. Postings, document lengths and a
+/// fingerprint of each indexed document are persisted; the total length is
+/// recomputed on load.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TextIndex {
+    postings: BTreeMap<String, BTreeMap<NodeIndex, u32>>, // token -> term frequency per node
+    doc_len: BTreeMap<NodeIndex, u32>,
+    #[serde(default)]
+    fingerprints: BTreeMap<NodeIndex, u64>, // of the text each node was indexed with
+    #[serde(skip)]
+    total_len: u64,
+}
+
+impl TextIndex {
+    pub fn len(&self) -> usize {
+        self.doc_len.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.doc_len.is_empty()
+    }
+
+    pub fn insert(&mut self, node: NodeIndex, text: &str) {
+        let tokens = tokenize(text);
+        self.total_len += tokens.len() as u64;
+        self.doc_len.insert(node, tokens.len() as u32);
+        self.fingerprints.insert(node, fingerprint(text));
+        for token in tokens {
+            *self
+                .postings
+                .entry(token)
+                .or_default()
+                .entry(node)
+                .or_default() += 1;
+        }
+    }
+
+    /// Drops `node`; `text` must be what it was indexed with.
+    pub fn remove(&mut self, node: NodeIndex, text: &str) {
+        let Some(len) = self.doc_len.remove(&node) else {
+            return;
+        };
+        self.total_len -= len as u64;
+        self.fingerprints.remove(&node);
+        for token in tokenize(text) {
+            if let Some(nodes) = self.postings.get_mut(&token) {
+                nodes.remove(&node);
+                if nodes.is_empty() {
+                    self.postings.remove(&token);
+                }
+            }
+        }
+    }
+
+    pub fn rebuild<I: IntoIterator<Item = (NodeIndex, String)>>(&mut self, items: I) {
+        self.postings.clear();
+        self.doc_len.clear();
+        self.fingerprints.clear();
+        self.total_len = 0;
+        for (node, text) in items {
+            self.insert(node, &text);
+        }
+    }
+
+    /// Recomputes the derived total after loading. Returns false when the
+    /// persisted index was not built from exactly `docs` (say the file was
+    /// edited by hand), in which case it must be rebuilt.
+    pub fn restore(&mut self, docs: &[(NodeIndex, String)]) -> bool {
+        self.total_len = self.doc_len.values().map(|&l| l as u64).sum();
+        self.doc_len.len() == docs.len()
+            && self.fingerprints.len() == docs.len()
+            && docs
+                .iter()
+                .all(|(node, text)| self.fingerprints.get(node) == Some(&fingerprint(text)))
+    }
+
+    /// Nodes matching any query token, best first. Every query token also
+    /// matches longer words it is a prefix of, at reduced weight.
+    pub fn search(&self, query: &str) -> Vec<(NodeIndex, f32)> {
+        if self.doc_len.is_empty() {
+            return vec![];
+        }
+        let docs = self.doc_len.len() as f32;
+        let avg_len = (self.total_len as f32 / docs).max(1.0);
+        let mut scores: HashMap<NodeIndex, f32> = HashMap::new();
+        for token in tokenize(query) {
+            // best expansion per node, so a prefix with many completions is not over-counted
+            let mut best: HashMap<NodeIndex, f32> = HashMap::new();
+            let start = Bound::Included(token.clone());
+            for (term, nodes) in self
+                .postings
+                .range((start, Bound::Unbounded))
+                .take_while(|(term, _)| term.starts_with(&token))
+            {
+                let weight = if *term == token { 1.0 } else { PREFIX_WEIGHT };
+                let df = nodes.len() as f32;
+                let idf = (1.0 + (docs - df + 0.5) / (df + 0.5)).ln();
+                for (&node, &tf) in nodes {
+                    let tf = tf as f32;
+                    let len = self.doc_len.get(&node).copied().unwrap_or(0) as f32;
+                    let norm = tf + BM25_K1 * (1.0 - BM25_B + BM25_B * len / avg_len);
+                    let score = weight * idf * tf * (BM25_K1 + 1.0) / norm;
+                    let entry = best.entry(node).or_default();
+                    *entry = entry.max(score);
+                }
+            }
+            for (node, score) in best {
+                *scores.entry(node).or_default() += score;
+            }
+        }
+        let mut ranked: Vec<(NodeIndex, f32)> = scores.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        ranked
+    }
+}
@@ -0,0 +1,341 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use petgraph::graph::NodeIndex;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_M: usize = 16;
+const DEFAULT_EF_CONSTRUCTION: usize = 100;
+const DEFAULT_EF_SEARCH: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum VectorMetric {
+    #[default]
+    Cosine,
+    Dot,
+    L2,
+}
+
+impl VectorMetric {
+    /// Lower is closer, whatever the metric.
+    pub fn distance(&self, a: &[f32], b: &[f32]) -> f32 {
+        match self {
+            VectorMetric::Cosine => 1.0 - cosine(a, b),
+            VectorMetric::Dot => -dot(a, b),
+            VectorMetric::L2 => a
+                .iter()
+                .zip(b)
+                .map(|(x, y)| (x - y) * (x - y))
+                .sum::<f32>()
+                .sqrt(),
+        }
+    }
+
+    /// Value reported to callers: similarity for cosine and dot, distance for L2.
+    pub fn score(&self, a: &[f32], b: &[f32]) -> f32 {
+        match self {
+            VectorMetric::Cosine => cosine(a, b),
+            VectorMetric::Dot => dot(a, b),
+            VectorMetric::L2 => self.distance(a, b),
+        }
+    }
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    let norm = dot(a, a).sqrt() * dot(b, b).sqrt();
+    if norm == 0.0 { 0.0 } else { dot(a, b) / norm }
+}
+
+// -----------HNSW INDEX---------------------------------
+/// Hierarchical navigable small world graph over node embeddings. Only the
+/// links are persisted; vectors are re-attached from the nodes on load.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorIndex {
+    pub metric: VectorMetric,
+    m: usize,
+    ef_construction: usize,
+    entry: Option<usize>,
+    dimensions: Option<usize>,
+    elements: Vec<Element>,
+    #[serde(skip)]
+    by_node: HashMap<NodeIndex, usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Element {
+    node: NodeIndex,
+    deleted: bool,              // replaced by a newer vector for the same node
+    neighbors: Vec<Vec<usize>>, // one list per level
+    #[serde(skip)]
+    vector: Vec<f32>,
+}
+
+impl Default for VectorIndex {
+    fn default() -> Self {
+        Self::new(VectorMetric::default())
+    }
+}
+
+impl VectorIndex {
+    pub fn new(metric: VectorMetric) -> Self {
+        Self {
+            metric,
+            m: DEFAULT_M,
+            ef_construction: DEFAULT_EF_CONSTRUCTION,
+            entry: None,
+            dimensions: None,
+            elements: vec![],
+            by_node: HashMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_node.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_node.is_empty()
+    }
+
+    /// Size of the indexed vectors, fixed by the first insert.
+    pub fn dimensions(&self) -> Option<usize> {
+        self.dimensions
+    }
+
+    pub fn check_dimensions(&self, vector: &[f32]) -> Result<(), String> {
+        match self.dimensions {
+            Some(d) if d != vector.len() => Err(format!(
+                "Error to index embedding: expected {} dimensions, got {}",
+                d,
+                vector.len()
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Adds or replaces the vector for `node`.
+    pub fn insert(&mut self, node: NodeIndex, vector: Vec<f32>) -> Result<(), String> {
+        self.check_dimensions(&vector)?;
+        self.dimensions = Some(vector.len());
+        if let Some(old) = self.by_node.remove(&node) {
+            self.elements[old].deleted = true;
+        }
+        let id = self.elements.len();
+        let level = self.random_level(id);
+        self.elements.push(Element {
+            node,
+            deleted: false,
+            neighbors: vec![vec![]; level + 1],
+            vector,
+        });
+        self.by_node.insert(node, id);
+        self.link(id, level);
+        Ok(())
+    }
+
+    /// `k` closest live entries as `(node, distance)`, closest first.
+    pub fn search(&self, query: &[f32], k: usize) -> Vec<(NodeIndex, f32)> {
+        let Some(mut current) = self.entry else {
+            return vec![];
+        };
+        if self.check_dimensions(query).is_err() {
+            return vec![];
+        }
+        for level in (1..self.elements[current].neighbors.len()).rev() {
+            current = self.greedy_closest(query, current, level);
+        }
+        let ef = DEFAULT_EF_SEARCH.max(k);
+        self.search_level(query, &[current], ef, 0)
+            .into_iter()
+            .filter(|(_, id)| !self.elements[*id].deleted)
+            .take(k)
+            .map(|(d, id)| (self.elements[id].node, d))
+            .collect()
+    }
+
+    /// Drops every entry and re-inserts `items`.
+    pub fn rebuild<I>(&mut self, items: I)
+    where
+        I: IntoIterator<Item = (NodeIndex, Vec<f32>)>,
+    {
+        *self = Self {
+            m: self.m,
+            ef_construction: self.ef_construction,
+            ..Self::new(self.metric)
+        };
+        for (node, vector) in items {
+            // ignore vectors whose size disagrees with the first one indexed
+            let _ = self.insert(node, vector);
+        }
+    }
+
+    /// Re-attaches vectors after deserialization. Returns false when the
+    /// persisted links no longer match `lookup`, so the caller can rebuild.
+    pub fn restore<'a, F>(&mut self, lookup: F) -> bool
+    where
+        F: Fn(NodeIndex) -> Option<&'a Vec<f32>>,
+    {
+        self.by_node.clear();
+        for (id, element) in self.elements.iter_mut().enumerate() {
+            if element.deleted {
+                element.vector = lookup(element.node).cloned().unwrap_or_default();
+                continue;
+            }
+            match lookup(element.node) {
+                Some(v) if Some(v.len()) == self.dimensions => element.vector = v.clone(),
+                _ => return false,
+            }
+            self.by_node.insert(element.node, id);
+        }
+        true
+    }
+
+    fn random_level(&self, id: usize) -> usize {
+        let ml = 1.0 / (self.m as f64).ln();
+        let u: f64 = StdRng::seed_from_u64(id as u64)
+            .r#gen::<f64>()
+            .max(f64::MIN_POSITIVE);
+        (-u.ln() * ml).floor() as usize
+    }
+
+    fn max_neighbors(&self, level: usize) -> usize {
+        if level == 0 { self.m * 2 } else { self.m }
+    }
+
+    fn distance(&self, query: &[f32], id: usize) -> f32 {
+        self.metric.distance(query, &self.elements[id].vector)
+    }
+
+    fn link(&mut self, id: usize, level: usize) {
+        let Some(entry) = self.entry else {
+            self.entry = Some(id);
+            return;
+        };
+        let query = self.elements[id].vector.clone();
+        let top = self.elements[entry].neighbors.len() - 1;
+        let mut current = entry;
+        for l in (level + 1..=top).rev() {
+            current = self.greedy_closest(&query, current, l);
+        }
+        let mut entry_points = vec![current];
+        for l in (0..=level.min(top)).rev() {
+            let found = self.search_level(&query, &entry_points, self.ef_construction, l);
+            let selected: Vec<usize> = found
+                .iter()
+                .take(self.max_neighbors(l))
+                .map(|(_, n)| *n)
+                .collect();
+            for &n in &selected {
+                self.elements[n].neighbors[l].push(id);
+                self.prune(n, l);
+            }
+            self.elements[id].neighbors[l] = selected;
+            entry_points = found.into_iter().map(|(_, n)| n).collect();
+        }
+        if level > top {
+            self.entry = Some(id);
+        }
+    }
+
+    // Keeps only the closest neighbours of `id` at `level`.
+    fn prune(&mut self, id: usize, level: usize) {
+        let max = self.max_neighbors(level);
+        if self.elements[id].neighbors[level].len() <= max {
+            return;
+        }
+        let base = self.elements[id].vector.clone();
+        let mut scored: Vec<(f32, usize)> = self.elements[id].neighbors[level]
+            .iter()
+            .map(|&n| (self.distance(&base, n), n))
+            .collect();
+        scored.sort_by(|a, b| a.0.total_cmp(&b.0));
+        self.elements[id].neighbors[level] = scored.into_iter().take(max).map(|(_, n)| n).collect();
+    }
+
+    fn greedy_closest(&self, query: &[f32], start: usize, level: usize) -> usize {
+        let mut current = start;
+        let mut best = self.distance(query, current);
+        loop {
+            let mut improved = false;
+            for &n in &self.elements[current].neighbors[level] {
+                let d = self.distance(query, n);
+                if d < best {
+                    best = d;
+                    current = n;
+                    improved = true;
+                }
+            }
+            if !improved {
+                return current;
+            }
+        }
+    }
+
+    // Best-first search at one level; returns up to `ef` (distance, id) pairs sorted.
+    fn search_level(
+        &self,
+        query: &[f32],
+        entry: &[usize],
+        ef: usize,
+        level: usize,
+    ) -> Vec<(f32, usize)> {
+        let mut visited: HashSet<usize> = entry.iter().copied().collect();
+        let mut candidates: BinaryHeap<Reverse<Scored>> = BinaryHeap::new();
+        let mut found: BinaryHeap<Scored> = BinaryHeap::new();
+        for &e in entry {
+            let s = Scored(self.distance(query, e), e);
+            candidates.push(Reverse(s));
+            found.push(s);
+        }
+        while let Some(Reverse(Scored(d, id))) = candidates.pop() {
+            if found.len() >= ef && found.peek().is_some_and(|w| d > w.0) {
+                break;
+            }
+            for &n in self.elements[id].neighbors.get(level).into_iter().flatten() {
+                if !visited.insert(n) {
+                    continue;
+                }
+                let dn = self.distance(query, n);
+                if found.len() < ef || found.peek().is_some_and(|w| dn < w.0) {
+                    candidates.push(Reverse(Scored(dn, n)));
+                    found.push(Scored(dn, n));
+                    if found.len() > ef {
+                        found.pop();
+                    }
+                }
+            }
+        }
+        let mut result: Vec<(f32, usize)> = found.into_iter().map(|s| (s.0, s.1)).collect();
+        result.sort_by(|a, b| a.0.total_cmp(&b.0));
+        result
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Scored(f32, usize);
+
+impl PartialEq for Scored {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Scored {}
+
+impl PartialOrd for Scored {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Scored {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0).then(self.1.cmp(&other.1))
+    }
+}
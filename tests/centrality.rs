@@ -0,0 +1,81 @@
+mod common;
+
+use std::collections::HashMap;
+
+use common::node;
+use graphos::models::graph_models::Grapho;
+use graphos::models::node_types::NodeType;
+use petgraph::graph::NodeIndex;
+use petgraph::{Directed, EdgeType, Undirected};
+use serde::{Deserialize, Serialize};
+
+fn path<Ty>(names: &[&str]) -> Grapho<NodeType, Ty>
+where
+    Ty: EdgeType + Serialize + for<'de> Deserialize<'de>,
+{
+    let mut g = Grapho::new("path");
+    for pair in names.windows(2) {
+        g.add_connection(node(pair[0]), node(pair[1]), "next", "")
+            .unwrap();
+    }
+    g
+}
+
+fn by_name<Ty>(g: &Grapho<NodeType, Ty>, scores: &HashMap<NodeIndex, f64>) -> Vec<f64>
+where
+    Ty: EdgeType + Serialize + for<'de> Deserialize<'de>,
+{
+    ["a", "b", "c", "d", "e"]
+        .iter()
+        .filter_map(|name| g.get_node_index_by_name(name))
+        .map(|idx| scores[&idx])
+        .collect()
+}
+
+fn assert_close(found: &[f64], expected: &[f64]) {
+    assert_eq!(found.len(), expected.len());
+    for (f, e) in found.iter().zip(expected) {
+        assert!((f - e).abs() < 1e-9, "{:?} != {:?}", found, expected);
+    }
+}
+
+#[test]
+fn betweenness_on_a_path_counts_pairs_through_each_node() {
+    let g = path::<Undirected>(&["a", "b", "c", "d", "e"]);
+    let scores = by_name(&g, &g.betweenness_centrality());
+    assert_close(&scores, &[0.0, 3.0, 4.0, 3.0, 0.0]);
+
+    // directed paths are only walked forwards, so pairs are not halved
+    let g = path::<Directed>(&["a", "b", "c"]);
+    assert_close(&by_name(&g, &g.betweenness_centrality()), &[0.0, 1.0, 0.0]);
+}
+
+#[test]
+fn closeness_on_a_path_favours_the_middle() {
+    let g = path::<Undirected>(&["a", "b", "c", "d", "e"]);
+    let scores = by_name(&g, &g.closeness_centrality());
+    assert_close(&scores, &[0.4, 4.0 / 7.0, 4.0 / 6.0, 4.0 / 7.0, 0.4]);
+
+    // an isolated pair only reaches a quarter of the other nodes
+    let mut split = path::<Undirected>(&["a", "b", "c"]);
+    split
+        .add_connection(node("d"), node("e"), "next", "")
+        .unwrap();
+    let scores = by_name(&split, &split.closeness_centrality());
+    assert_close(&scores[3..], &[0.25, 0.25]);
+}
+
+#[test]
+fn eigenvector_and_pagerank_rank_the_hub_first() {
+    let mut g = Grapho::<NodeType, Undirected>::new("star");
+    for leaf in ["b", "c", "d", "e"] {
+        g.add_connection(node("a"), node(leaf), "link", "").unwrap();
+    }
+    for scores in [g.eigenvector_centrality(), g.pagerank(0.85)] {
+        let scores = by_name(&g, &scores);
+        assert!(scores[1..].iter().all(|&leaf| leaf < scores[0]));
+        assert_close(&scores[2..], &[scores[1]; 3]);
+    }
+    let total: f64 = g.pagerank(0.85).values().sum();
+    assert!((total - 1.0).abs() < 1e-9);
+}
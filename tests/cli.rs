@@ -0,0 +1,42 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("graphos-cli-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn run(dir: &PathBuf, args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_graphos"))
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn malformed_commands_print_usage_without_writing_files() {
+    let dir = scratch_dir("usage");
+    for args in [
+        &["diff", "old.json"][..],
+        &["dif", "old.json", "new.json"],
+        &["stats", "a.json", "b.json"],
+        &["--help"],
+    ] {
+        let output = run(&dir, args);
+        assert_eq!(output.status.code(), Some(2), "{:?}", args);
+        assert!(String::from_utf8_lossy(&output.stderr).contains("Usage"));
+    }
+    assert!(!dir.join("files").exists());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn missing_input_fails_without_writing_files() {
+    let dir = scratch_dir("missing");
+    let output = run(&dir, &["diff", "old.json", "new.json"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(!dir.join("files").exists());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,43 @@
+// Fixtures shared by the integration tests; each test crate uses a subset.
+#![allow(dead_code)]
+
+use graphos::models::graph_models::{Grapho, Node, Ref};
+use graphos::models::node_types::NodeType;
+use petgraph::EdgeType;
+use petgraph::graph::NodeIndex;
+use serde::{Deserialize, Serialize};
+
+/// A node whose content is its own name.
+pub fn node(name: &str) -> Node<NodeType> {
+    Node::new(name, NodeType::Text(name.into()), "")
+}
+
+/// A file path in the temp dir, unique to this test process.
+pub fn temp_path(name: &str) -> String {
+    std::env::temp_dir()
+        .join(format!("graphos-{}-{}.json", name, std::process::id()))
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Saves `g` under `name`, loads it back and removes the file.
+pub fn round_trip<Ty>(g: &Grapho<NodeType, Ty>, name: &str) -> Grapho<NodeType, Ty>
+where
+    Ty: EdgeType + Serialize + for<'de> Deserialize<'de>,
+{
+    let path = temp_path(name);
+    g.save_to_file(&path).unwrap();
+    let loaded = Grapho::load_from_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    loaded
+}
+
+/// Node names of `refs`, sorted.
+pub fn names<I>(refs: I) -> Vec<String>
+where
+    I: IntoIterator<Item = Ref<NodeIndex>>,
+{
+    let mut names: Vec<String> = refs.into_iter().map(|r| r.name).collect();
+    names.sort();
+    names
+}
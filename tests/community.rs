@@ -0,0 +1,82 @@
+mod common;
+
+use std::collections::HashMap;
+
+use common::{node, round_trip};
+use graphos::models::graph_models::Grapho;
+use graphos::models::node_types::NodeType;
+use petgraph::Undirected;
+use petgraph::graph::NodeIndex;
+
+type Graph = Grapho<NodeType, Undirected>;
+
+// Triangles a-b-c and x-y-z joined by the c-x bridge.
+fn two_triangles() -> Graph {
+    let mut g = Graph::new("triangles");
+    for (from, to) in [
+        ("a", "b"),
+        ("b", "c"),
+        ("c", "a"),
+        ("x", "y"),
+        ("y", "z"),
+        ("z", "x"),
+        ("c", "x"),
+    ] {
+        g.add_connection(node(from), node(to), "link", "").unwrap();
+    }
+    g
+}
+
+fn community(g: &Graph, communities: &HashMap<NodeIndex, usize>, name: &str) -> usize {
+    communities[&g.get_node_index_by_name(name).unwrap()]
+}
+
+fn assert_split_at_the_bridge(g: &Graph, communities: &HashMap<NodeIndex, usize>) {
+    assert_eq!(communities.len(), 6);
+    let left = community(g, communities, "a");
+    let right = community(g, communities, "x");
+    assert_ne!(left, right);
+    for name in ["b", "c"] {
+        assert_eq!(community(g, communities, name), left, "{}", name);
+    }
+    for name in ["y", "z"] {
+        assert_eq!(community(g, communities, name), right, "{}", name);
+    }
+}
+
+#[test]
+fn louvain_splits_two_triangles_at_the_bridge() {
+    let g = two_triangles();
+    let communities = g.louvain_communities();
+    assert_split_at_the_bridge(&g, &communities);
+    // numbered from 0 in node order
+    assert_eq!(community(&g, &communities, "a"), 0);
+    assert_eq!(community(&g, &communities, "x"), 1);
+}
+
+#[test]
+fn label_propagation_splits_two_triangles_and_is_seeded() {
+    let g = two_triangles();
+    for seed in 0..10 {
+        let communities = g.label_propagation_communities(seed);
+        assert_split_at_the_bridge(&g, &communities);
+        assert_eq!(communities, g.label_propagation_communities(seed));
+    }
+}
+
+#[test]
+fn assigned_communities_survive_save_and_load() {
+    let mut g = two_triangles();
+    let communities = g.louvain_communities();
+    g.assign_communities(&communities);
+    let loaded = round_trip(&g, "communities");
+    for idx in loaded.core.node_indices() {
+        let name = &loaded.core[idx].name;
+        assert_eq!(
+            loaded.core[idx].metadata.community,
+            Some(community(&g, &communities, name)),
+            "{}",
+            name
+        );
+    }
+}
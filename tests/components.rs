@@ -0,0 +1,53 @@
+mod common;
+
+use common::{names, node};
+use graphos::models::graph_models::Grapho;
+use graphos::models::node_types::NodeType;
+use petgraph::{Directed, EdgeType, Undirected};
+use serde::{Deserialize, Serialize};
+
+fn graph<Ty>(edges: &[(&str, &str)]) -> Grapho<NodeType, Ty>
+where
+    Ty: EdgeType + Serialize + for<'de> Deserialize<'de>,
+{
+    let mut g = Grapho::new("g");
+    for (from, to) in edges {
+        g.add_connection(node(from), node(to), "link", "").unwrap();
+    }
+    g
+}
+
+fn groups<Ty>(g: &Grapho<NodeType, Ty>) -> Vec<Vec<String>>
+where
+    Ty: EdgeType + Serialize + for<'de> Deserialize<'de>,
+{
+    g.components().into_iter().map(names).collect()
+}
+
+#[test]
+fn undirected_components_are_largest_first() {
+    let g = graph::<Undirected>(&[("a", "b"), ("b", "c"), ("x", "y")]);
+    assert_eq!(groups(&g), [vec!["a", "b", "c"], vec!["x", "y"]]);
+    assert_eq!(g.component_sizes(), [3, 2]);
+}
+
+#[test]
+fn directed_graphs_report_strong_components() {
+    let g = graph::<Directed>(&[("a", "b"), ("b", "c"), ("c", "a"), ("c", "d")]);
+    assert_eq!(groups(&g), [vec!["a", "b", "c"], vec!["d"]]);
+    assert_eq!(g.weakly_connected_components().len(), 1);
+}
+
+#[test]
+fn split_components_builds_one_graph_each() {
+    let g = graph::<Undirected>(&[("a", "b"), ("b", "c"), ("x", "y")]);
+    let parts = g.split_components();
+    assert_eq!(parts.len(), 2);
+    assert_eq!(parts[0].name, "g-component-0");
+    assert_eq!(names(parts[0].runtime_ref.nodes.clone()), ["a", "b", "c"]);
+    assert_eq!(parts[0].core.edge_count(), 2);
+    assert_eq!(names(parts[1].runtime_ref.nodes.clone()), ["x", "y"]);
+    assert_eq!(parts[1].core.edge_count(), 1);
+    let x = parts[1].get_node_index_by_name("x").unwrap();
+    assert_eq!(parts[1].core[x].connections[0].node.name, "y");
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use common::node;
+use graphos::models::graph_models::Grapho;
+use graphos::models::node_types::NodeType;
+use petgraph::Directed;
+
+type Graph = Grapho<NodeType, Directed>;
+
+fn graph(edges: &[(&str, &str)]) -> Graph {
+    let mut g = Graph::new("deps");
+    for (from, to) in edges {
+        g.add_connection(node(from), node(to), "depends", "")
+            .unwrap();
+    }
+    g
+}
+
+fn position(order: &[String], name: &str) -> usize {
+    order.iter().position(|n| n == name).unwrap()
+}
+
+#[test]
+fn a_dag_has_a_topological_order() {
+    let edges = [("a", "b"), ("a", "c"), ("b", "d"), ("c", "d"), ("d", "e")];
+    let g = graph(&edges);
+    assert!(!g.has_cycle());
+    assert!(g.find_cycles().is_empty());
+    let order: Vec<String> = g
+        .topological_order()
+        .unwrap()
+        .into_iter()
+        .map(|r| r.name)
+        .collect();
+    assert_eq!(order.len(), 5);
+    for (from, to) in edges {
+        assert!(
+            position(&order, from) < position(&order, to),
+            "{} {}",
+            from,
+            to
+        );
+    }
+}
+
+#[test]
+fn cycles_are_found_per_strong_component() {
+    let g = graph(&[
+        ("a", "b"),
+        ("b", "c"),
+        ("c", "a"),
+        ("c", "d"),
+        ("d", "e"),
+        ("e", "d"),
+        ("e", "f"),
+    ]);
+    assert!(g.has_cycle());
+    let mut cycles: Vec<Vec<String>> = g
+        .find_cycles()
+        .into_iter()
+        .map(|c| c.into_iter().map(|r| r.name).collect())
+        .collect();
+    cycles.sort();
+    assert_eq!(cycles, [vec!["a", "b", "c", "a"], vec!["d", "e", "d"]]);
+
+    let err = g.topological_order().unwrap_err();
+    assert!(
+        err.starts_with("Error to sort graph: cycle detected: "),
+        "{}",
+        err
+    );
+    let path: Vec<&str> = err.rsplit(": ").next().unwrap().split(" -> ").collect();
+    assert_eq!(path.first(), path.last());
+    assert!(path.len() == 3 || path.len() == 4, "{}", err);
+}
+
+#[test]
+fn a_self_loop_is_a_cycle() {
+    let g = graph(&[("a", "b"), ("b", "b")]);
+    assert!(g.has_cycle());
+    let cycles: Vec<Vec<String>> = g
+        .find_cycles()
+        .into_iter()
+        .map(|c| c.into_iter().map(|r| r.name).collect())
+        .collect();
+    assert_eq!(cycles, [vec!["b", "b"]]);
+    assert_eq!(
+        g.topological_order().unwrap_err(),
+        "Error to sort graph: cycle detected: b -> b"
+    );
+}
@@ -0,0 +1,106 @@
+mod common;
+
+use common::{names, node};
+use graphos::algorithms::diff::ConnectionKey;
+use graphos::models::graph_models::Grapho;
+use graphos::models::node_types::NodeType;
+use petgraph::Undirected;
+
+type Graph = Grapho<NodeType, Undirected>;
+
+fn base() -> Graph {
+    let mut g = Graph::new("g");
+    g.add_connection(node("a"), node("b"), "friendship", "")
+        .unwrap();
+    g.add_connection(node("b"), node("c"), "friendship", "")
+        .unwrap();
+    g
+}
+
+fn key(from: &str, to: &str, edge_kind: &str) -> ConnectionKey {
+    ConnectionKey {
+        from: from.into(),
+        to: to.into(),
+        edge_kind: edge_kind.into(),
+    }
+}
+
+#[test]
+fn identical_graphs_have_no_differences() {
+    let g = base();
+    let diff = g.diff(&g.subgraph(g.core.node_indices()));
+    assert!(diff.is_empty());
+    assert_eq!(diff.to_string(), "No differences.\n");
+
+    // rebuilt graphs have fresh ids but the same names
+    assert!(g.diff(&base()).is_empty());
+}
+
+#[test]
+fn renames_are_matched_by_uuid() {
+    let old = base();
+    let keep = ["a", "b"].map(|n| old.get_node_index_by_name(n).unwrap());
+    let mut new = old.subgraph(keep);
+    let b = new.get_node_index_by_name("b").unwrap();
+    new.core[b].name = "bee".into();
+    let mut new = new.subgraph(new.core.node_indices());
+    new.add_connection(node("bee"), node("d"), "colleague", "")
+        .unwrap();
+
+    let diff = old.diff(&new);
+    assert_eq!(names(diff.added_nodes.clone()), ["d"]);
+    assert_eq!(names(diff.removed_nodes.clone()), ["c"]);
+    assert_eq!(diff.changed_nodes.len(), 1);
+    let change = &diff.changed_nodes[0];
+    assert_eq!(change.name, "bee");
+    assert_eq!(change.id, old.core[keep[1]].metadata.id);
+    assert_eq!(change.fields.len(), 1);
+    assert_eq!(
+        (
+            change.fields[0].field.as_str(),
+            change.fields[0].before.as_str()
+        ),
+        ("name", "\"b\"")
+    );
+    assert_eq!(change.fields[0].after, "\"bee\"");
+
+    // a-b survives the rename, so it is neither added nor removed
+    assert_eq!(diff.removed_connections, [key("bee", "c", "friendship")]);
+    assert_eq!(diff.added_connections, [key("bee", "d", "colleague")]);
+}
+
+#[test]
+fn parallel_connections_and_edge_kinds_are_counted() {
+    let old = base();
+    let mut new = old.subgraph(old.core.node_indices());
+    new.add_parallel_connection(node("a"), node("b"), "friendship", "")
+        .unwrap();
+    new.add_connection(node("c"), node("a"), "colleague", "")
+        .unwrap();
+
+    let diff = old.diff(&new);
+    assert!(diff.added_nodes.is_empty() && diff.changed_nodes.is_empty());
+    assert_eq!(
+        diff.added_connections,
+        [key("a", "b", "friendship"), key("a", "c", "colleague")]
+    );
+    let kinds: Vec<_> = diff
+        .edge_kind_changes
+        .iter()
+        .map(|k| (k.name.as_str(), k.before, k.after))
+        .collect();
+    assert_eq!(
+        kinds,
+        [
+            ("colleague", None, Some(1.0)),
+            ("friendship", Some(2.0), Some(3.0)),
+        ]
+    );
+
+    let reverse = new.diff(&old);
+    assert_eq!(reverse.removed_connections.len(), 2);
+    assert!(reverse.added_connections.is_empty());
+    let text = reverse.to_string();
+    assert!(text.contains("- connection a -[colleague]- c"));
+    assert!(text.contains("~ edge kind colleague: energy 1.00 -> -"));
+}
@@ -0,0 +1,193 @@
+mod common;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use chrono::{DateTime, Utc};
+use common::{node, round_trip};
+use graphos::algorithms::centrality::Centrality;
+use graphos::models::energy::EnergyPolicy;
+use graphos::models::graph_models::Grapho;
+use graphos::models::node_types::NodeType;
+use petgraph::Undirected;
+use petgraph::graph::NodeIndex;
+
+fn names(g: &Grapho<NodeType, Undirected>, k: usize) -> Vec<String> {
+    g.top_k_nodes_by_energy(k)
+        .into_iter()
+        .map(|r| r.name)
+        .collect()
+}
+
+#[test]
+fn global_policy_defers_recompute_until_refresh() {
+    let mut g = Grapho::<NodeType, Undirected>::new("g");
+    g.set_energy_measure(Centrality::PageRank { damping: 0.85 });
+    assert!(!g.energy_is_stale());
+
+    for leaf in ["b", "c", "d"] {
+        g.add_connection(node("hub"), node(leaf), "link", "")
+            .unwrap();
+    }
+    assert!(g.energy_is_stale());
+    // stale energies are bypassed, so the ranking is already right
+    assert_eq!(names(&g, 1), vec!["hub"]);
+
+    g.refresh_energy();
+    assert!(!g.energy_is_stale());
+    let hub = g.get_node_index_by_name("hub").unwrap();
+    let ranks = g.pagerank(0.85);
+    assert!((g.core[hub].energy - ranks[&hub]).abs() < 1e-9);
+    assert_eq!(names(&g, 1), vec!["hub"]);
+}
+
+#[test]
+fn local_policy_is_never_stale() {
+    let mut g = Grapho::<NodeType, Undirected>::new("g");
+    g.add_connection(node("a"), node("b"), "link", "").unwrap();
+    g.add_connection(node("a"), node("c"), "link", "").unwrap();
+    assert!(!g.energy_is_stale());
+    let a = g.get_node_index_by_name("a").unwrap();
+    assert_eq!(g.core[a].energy, 2.0);
+}
+
+#[test]
+fn policy_survives_save_and_load() {
+    use graphos::models::energy::{EnergySpec, TimeDecayedEnergy};
+
+    let mut g = Grapho::<NodeType, Undirected>::new("g");
+    g.add_connection(node("a"), node("b"), "link", "").unwrap();
+    g.add_connection(node("b"), node("c"), "link", "").unwrap();
+    g.set_energy_measure(Centrality::PageRank { damping: 0.85 });
+    let mut loaded = round_trip(&g, "pagerank");
+    assert_eq!(
+        loaded.energy_spec(),
+        EnergySpec::Centrality(Centrality::PageRank { damping: 0.85 })
+    );
+    loaded
+        .add_connection(node("c"), node("d"), "link", "")
+        .unwrap();
+    loaded.refresh_energy();
+    let ranks = loaded.pagerank(0.85);
+    for idx in loaded.core.node_indices() {
+        assert!((loaded.core[idx].energy - ranks[&idx]).abs() < 1e-9);
+    }
+
+    let decayed = TimeDecayedEnergy::new(chrono::Duration::hours(6)).with_access_weight(0.5);
+    g.set_energy_policy(decayed);
+    let loaded = round_trip(&g, "decayed");
+    assert_eq!(
+        loaded.energy_spec(),
+        EnergySpec::TimeDecayed {
+            half_life_ms: 6 * 3600 * 1000,
+            access_weight: 0.5
+        }
+    );
+}
+
+#[test]
+fn custom_policy_loads_as_recomputed_degree() {
+    use graphos::models::energy::{EnergySpec, FnEnergy};
+
+    let mut g = Grapho::<NodeType, Undirected>::new("g");
+    g.add_connection(node("a"), node("b"), "link", "").unwrap();
+    g.add_connection(node("a"), node("c"), "link", "").unwrap();
+    g.set_energy_policy(FnEnergy(|_: &Grapho<NodeType, Undirected>, _, _| 100.0));
+    assert_eq!(g.energy_spec(), EnergySpec::Custom);
+    let loaded = round_trip(&g, "custom");
+    assert_eq!(loaded.energy_spec(), EnergySpec::Degree);
+    let a = loaded.get_node_index_by_name("a").unwrap();
+    let b = loaded.get_node_index_by_name("b").unwrap();
+    assert_eq!(loaded.core[a].energy, 2.0);
+    assert_eq!(loaded.core[b].energy, 1.0);
+}
+
+#[test]
+fn time_decay_applies_to_each_access() {
+    use chrono::{Duration, Utc};
+    use graphos::models::energy::TimeDecayedEnergy;
+
+    let mut g = Grapho::<NodeType, Undirected>::new("g");
+    g.add_connection(node("a"), node("b"), "link", "").unwrap();
+    let a = g.get_node_index_by_name("a").unwrap();
+    let month_ago = Utc::now() - Duration::days(30);
+    g.core[a].access_count = 1000;
+    g.core[a].last_accessed_at = Some(month_ago);
+    g.core[a].recent_accesses = std::iter::repeat_n(month_ago, 64).collect();
+    g.set_energy_policy(TimeDecayedEnergy::new(Duration::days(1)).with_access_weight(1.0));
+    g.record_access(a);
+
+    // one fresh connection plus one fresh access; the thousand old ones have decayed away
+    assert!(
+        (g.core[a].energy - 2.0).abs() < 0.01,
+        "{}",
+        g.core[a].energy
+    );
+    assert_eq!(g.core[a].recent_accesses.len(), 64);
+    assert_eq!(g.core[a].access_count, 1001);
+}
+
+static SINGLE: AtomicUsize = AtomicUsize::new(0);
+static WHOLE: AtomicUsize = AtomicUsize::new(0);
+
+// PageRank that counts how it is asked for scores.
+#[derive(Debug)]
+struct CountingPageRank;
+
+impl EnergyPolicy<NodeType, Undirected> for CountingPageRank {
+    fn node_energy(
+        &self,
+        graph: &Grapho<NodeType, Undirected>,
+        node: NodeIndex,
+        now: DateTime<Utc>,
+    ) -> f64 {
+        SINGLE.fetch_add(1, Ordering::SeqCst);
+        EnergyPolicy::<NodeType, Undirected>::node_energy(&PAGERANK, graph, node, now)
+    }
+
+    fn node_energies(&self, graph: &Grapho<NodeType, Undirected>, now: DateTime<Utc>) -> Vec<f64> {
+        WHOLE.fetch_add(1, Ordering::SeqCst);
+        EnergyPolicy::<NodeType, Undirected>::node_energies(&PAGERANK, graph, now)
+    }
+
+    fn is_local(&self) -> bool {
+        false
+    }
+}
+
+const PAGERANK: Centrality = Centrality::PageRank { damping: 0.85 };
+
+#[test]
+fn global_policy_is_scored_for_the_whole_graph_at_once() {
+    let mut g = Grapho::<NodeType, Undirected>::new("g");
+    g.set_energy_policy(CountingPageRank);
+    let before = WHOLE.load(Ordering::SeqCst);
+    for i in 0..20 {
+        g.add_connection(node("hub"), node(&format!("leaf{}", i)), "link", "")
+            .unwrap();
+    }
+    let hub = g.get_node_index_by_name("hub").unwrap();
+    for _ in 0..5 {
+        g.record_access(hub);
+    }
+    assert_eq!(WHOLE.load(Ordering::SeqCst), before);
+
+    g.refresh_energy();
+    g.refresh_energy();
+    assert_eq!(WHOLE.load(Ordering::SeqCst), before + 1);
+    assert_eq!(SINGLE.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn degree_centrality_is_scored_per_node() {
+    let mut g = Grapho::<NodeType, Undirected>::new("g");
+    g.set_energy_measure(Centrality::Degree);
+    g.add_connection(node("a"), node("b"), "link", "").unwrap();
+    g.add_connection(node("a"), node("c"), "link", "").unwrap();
+    assert!(!g.energy_is_stale());
+    let a = g.get_node_index_by_name("a").unwrap();
+    assert_eq!(g.core[a].energy, 2.0);
+    assert_eq!(
+        EnergyPolicy::<NodeType, Undirected>::node_energy(&Centrality::Degree, &g, a, Utc::now()),
+        2.0
+    );
+}
@@ -0,0 +1,192 @@
+mod common;
+
+use common::node;
+use graphos::algorithms::merge::{MergeSide, MergeStrategy};
+use graphos::algorithms::set_ops::NodeIdentity;
+use graphos::models::graph_models::Grapho;
+use graphos::models::node_types::NodeType;
+use petgraph::Undirected;
+
+type Graph = Grapho<NodeType, Undirected>;
+
+fn base() -> Graph {
+    let mut g = Graph::new("g");
+    g.add_connection(node("a"), node("b"), "friendship", "")
+        .unwrap();
+    g.add_connection(node("b"), node("c"), "friendship", "")
+        .unwrap();
+    g
+}
+
+// Independent copies that keep node and edge ids.
+fn copy(g: &Graph) -> Graph {
+    g.subgraph(g.core.node_indices())
+}
+
+fn content(g: &Graph, name: &str) -> NodeType {
+    g.core[g.get_node_index_by_name(name).unwrap()]
+        .content
+        .clone()
+}
+
+fn set_content(g: &mut Graph, name: &str, text: &str) {
+    let idx = g.get_node_index_by_name(name).unwrap();
+    g.set_content(idx, NodeType::Text(text.into())).unwrap();
+}
+
+#[test]
+fn disjoint_changes_merge_cleanly() {
+    let base = base();
+    let (mut ours, mut theirs) = (copy(&base), copy(&base));
+    set_content(&mut ours, "a", "ours");
+    let b = theirs.get_node_index_by_name("b").unwrap();
+    theirs.core[b].metadata.description = "theirs".into();
+    ours.add_connection(node("a"), node("o"), "friendship", "")
+        .unwrap();
+    theirs
+        .add_connection(node("c"), node("t"), "colleague", "")
+        .unwrap();
+
+    let (merged, report) = Graph::merge(&base, &ours, &theirs, MergeStrategy::Ours).unwrap();
+    assert!(report.is_clean());
+    assert_eq!(merged.core.node_count(), 5);
+    assert_eq!(merged.core.edge_count(), 4);
+    assert_eq!(content(&merged, "a"), NodeType::Text("ours".into()));
+    let b = merged.get_node_index_by_name("b").unwrap();
+    assert_eq!(merged.core[b].metadata.description, "theirs");
+}
+
+#[test]
+fn concurrent_edits_follow_the_strategy() {
+    let base = base();
+    let (mut ours, mut theirs) = (copy(&base), copy(&base));
+    set_content(&mut ours, "a", "ours");
+    std::thread::sleep(std::time::Duration::from_millis(2));
+    set_content(&mut theirs, "a", "theirs");
+
+    for (strategy, expected, side) in [
+        (MergeStrategy::Ours, "ours", MergeSide::Ours),
+        (MergeStrategy::Theirs, "theirs", MergeSide::Theirs),
+        (MergeStrategy::Newest, "theirs", MergeSide::Theirs),
+    ] {
+        let (merged, report) = Graph::merge(&base, &ours, &theirs, strategy).unwrap();
+        assert_eq!(content(&merged, "a"), NodeType::Text(expected.into()));
+        assert_eq!(report.conflicts.len(), 1);
+        let conflict = &report.conflicts[0];
+        assert_eq!(
+            (conflict.name.as_str(), conflict.field.as_str()),
+            ("a", "content")
+        );
+        assert_eq!(conflict.resolved_with, side);
+    }
+}
+
+#[test]
+fn deletion_of_an_untouched_node_wins() {
+    let base = base();
+    let ours = copy(&base);
+    let c = base.get_node_index_by_name("c").unwrap();
+    let keep = base.core.node_indices().filter(|&i| i != c);
+    let theirs = base.subgraph(keep);
+
+    let (merged, report) = Graph::merge(&base, &ours, &theirs, MergeStrategy::Ours).unwrap();
+    assert!(report.is_clean());
+    assert!(merged.get_node_index_by_name("c").is_none());
+    assert_eq!(merged.core.edge_count(), 1);
+}
+
+#[test]
+fn delete_versus_modify_is_a_conflict() {
+    let base = base();
+    let mut ours = copy(&base);
+    set_content(&mut ours, "c", "changed");
+    let c = base.get_node_index_by_name("c").unwrap();
+    let theirs = base.subgraph(base.core.node_indices().filter(|&i| i != c));
+
+    let (merged, report) = Graph::merge(&base, &ours, &theirs, MergeStrategy::Newest).unwrap();
+    assert_eq!(report.conflicts.len(), 1);
+    assert_eq!(report.conflicts[0].theirs, None);
+    assert_eq!(content(&merged, "c"), NodeType::Text("changed".into()));
+}
+
+#[test]
+fn deleting_a_modified_edge_is_a_conflict() {
+    let base = base();
+    let [a, b, c] = ["a", "b", "c"].map(|n| base.get_node_index_by_name(n).unwrap());
+    // theirs drops the b-c edge but keeps both nodes
+    let theirs = base
+        .subgraph([a, b])
+        .union(&base.subgraph([c]), NodeIdentity::Uuid)
+        .unwrap();
+    let (merged, report) = Graph::merge(&base, &copy(&base), &theirs, MergeStrategy::Ours).unwrap();
+    assert!(report.is_clean());
+    assert_eq!(merged.core.edge_count(), 1);
+
+    let mut ours = copy(&base);
+    let bc = base.find_edge(b, c, "friendship").unwrap();
+    ours.set_edge_prop(bc, "since", NodeType::Num(2020))
+        .unwrap();
+    for (strategy, edges) in [(MergeStrategy::Ours, 2), (MergeStrategy::Theirs, 1)] {
+        let (merged, report) = Graph::merge(&base, &ours, &theirs, strategy).unwrap();
+        assert_eq!(merged.core.edge_count(), edges);
+        assert_eq!(report.conflicts.len(), 1);
+        let conflict = &report.conflicts[0];
+        assert_eq!((conflict.id, conflict.field.as_str()), (bc, "edge"));
+        assert!(conflict.ours.is_some() && conflict.theirs.is_none());
+    }
+    let (merged, _) = Graph::merge(&base, &ours, &theirs, MergeStrategy::Newest).unwrap();
+    assert_eq!(
+        merged.get_edge_prop(bc, "since"),
+        Some(&NodeType::Num(2020))
+    );
+}
+
+#[test]
+fn nodes_added_with_the_same_name_are_kept_apart() {
+    let base = base();
+    let (mut ours, mut theirs) = (copy(&base), copy(&base));
+    ours.add_connection(node("a"), node("z"), "friendship", "")
+        .unwrap();
+    theirs
+        .add_connection(node("c"), node("z"), "colleague", "")
+        .unwrap();
+
+    let (merged, report) = Graph::merge(&base, &ours, &theirs, MergeStrategy::Ours).unwrap();
+    assert_eq!(merged.core.node_count(), 5);
+    assert_eq!(report.conflicts.len(), 1);
+    let conflict = &report.conflicts[0];
+    assert_eq!(
+        (conflict.name.as_str(), conflict.field.as_str()),
+        ("z", "name")
+    );
+    assert_eq!(conflict.resolved_with, MergeSide::Ours);
+
+    // ours keeps the name, theirs is renamed and keeps its own edge
+    let z = merged.get_node_index_by_name("z").unwrap();
+    assert_eq!(merged.core[z].connections[0].node.name, "a");
+    let renamed = merged
+        .core
+        .node_indices()
+        .find(|&i| merged.core[i].metadata.id == conflict.id)
+        .unwrap();
+    assert!(merged.core[renamed].name.starts_with("z~"));
+    assert_eq!(merged.core[renamed].connections[0].node.name, "c");
+}
+
+#[test]
+fn rename_onto_a_name_the_other_side_added_is_reported() {
+    let base = base();
+    let (mut ours, mut theirs) = (copy(&base), copy(&base));
+    let a = ours.get_node_index_by_name("a").unwrap();
+    ours.core[a].name = "x".into();
+    theirs
+        .add_connection(node("c"), node("x"), "colleague", "")
+        .unwrap();
+
+    let (merged, report) = Graph::merge(&base, &ours, &theirs, MergeStrategy::Theirs).unwrap();
+    assert_eq!(merged.core.node_count(), 4);
+    assert_eq!(report.conflicts.len(), 1);
+    assert_eq!(report.conflicts[0].field, "name");
+    let x = merged.get_node_index_by_name("x").unwrap();
+    assert_eq!(merged.core[x].connections[0].node.name, "c");
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use common::{names, node};
+use graphos::algorithms::embedding::WalkConfig;
+use graphos::models::graph_models::Grapho;
+use graphos::models::node_types::NodeType;
+use graphos::models::props::PropFilter;
+use petgraph::graph::NodeIndex;
+use petgraph::{Directed, Undirected};
+
+// a -> b -> c and a -> c, all "follows".
+fn chain() -> Grapho<NodeType, Directed> {
+    let mut g = Grapho::new("chain");
+    g.add_connection(node("a"), node("b"), "follows", "")
+        .unwrap();
+    g.add_connection(node("b"), node("c"), "follows", "")
+        .unwrap();
+    g.add_connection(node("a"), node("c"), "follows", "")
+        .unwrap();
+    g
+}
+
+fn idx(g: &Grapho<NodeType, Directed>, name: &str) -> NodeIndex {
+    g.get_node_index_by_name(name).unwrap()
+}
+
+#[test]
+fn directed_neighbour_queries_follow_edge_direction() {
+    let mut g = chain();
+    let (a, b, c) = (idx(&g, "a"), idx(&g, "b"), idx(&g, "c"));
+    g.add_label(a, "start").unwrap();
+    g.add_label(c, "end").unwrap();
+    g.set_prop(a, "rank", NodeType::Num(1)).unwrap();
+    g.set_prop(c, "rank", NodeType::Num(2)).unwrap();
+
+    assert_eq!(names(g.neighbors_by_edge_kind(b, "follows")), ["c"]);
+    assert!(g.neighbors_by_edge_kind(c, "follows").is_empty());
+    assert!(g.neighbors_with_label(b, "start", None).is_empty());
+    assert_eq!(names(g.neighbors_with_label(a, "end", None)), ["c"]);
+    let ranked = PropFilter::Exists("rank".into());
+    assert_eq!(names(g.neighbors_where(b, Some("follows"), &ranked)), ["c"]);
+    assert!(g.neighbors_where(c, None, &ranked).is_empty());
+}
+
+#[test]
+fn undirected_neighbour_queries_see_both_ends() {
+    let mut g = Grapho::<NodeType, Undirected>::new("g");
+    g.add_connection(node("a"), node("b"), "friendship", "")
+        .unwrap();
+    g.add_connection(node("b"), node("c"), "friendship", "")
+        .unwrap();
+    let [a, b, c] = ["a", "b", "c"].map(|n| g.get_node_index_by_name(n).unwrap());
+    assert_eq!(names(g.neighbors_by_edge_kind(b, "friendship")), ["a", "c"]);
+    assert_eq!(names(g.common_neighbors(a, c)), ["b"]);
+}
+
+#[test]
+fn directed_link_scores_use_successors() {
+    let g = chain();
+    let (a, b, c) = (idx(&g, "a"), idx(&g, "b"), idx(&g, "c"));
+    // a and b both point at c; c points nowhere
+    assert_eq!(names(g.common_neighbors(a, b)), ["c"]);
+    assert!(g.common_neighbors(b, c).is_empty());
+    assert_eq!(g.jaccard(a, b), 0.5);
+    assert_eq!(g.preferential_attachment(a, c), 0.0);
+}
+
+#[test]
+fn directed_walks_never_step_against_an_edge() {
+    let g = chain();
+    let walks = g.random_walks(&WalkConfig {
+        walk_length: 5,
+        walks_per_node: 20,
+        ..WalkConfig::default()
+    });
+    assert!(!walks.is_empty());
+    for walk in &walks {
+        for step in walk.windows(2) {
+            assert!(
+                g.core.find_edge(step[0], step[1]).is_some(),
+                "{:?} steps against an edge",
+                walk
+            );
+        }
+    }
+    // c is a sink, so every walk from it stops immediately
+    let c = idx(&g, "c");
+    assert!(walks.iter().filter(|w| w[0] == c).all(|w| w.len() == 1));
+}
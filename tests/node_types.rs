@@ -0,0 +1,80 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashSet};
+
+use graphos::models::node_types::NodeType;
+
+fn tricky_values() -> Vec<NodeType> {
+    let big = isize::MAX as f64; // rounds up to 2^63
+    vec![
+        NodeType::Null,
+        NodeType::Bool(false),
+        NodeType::Bool(true),
+        NodeType::Num(isize::MIN),
+        NodeType::Num(-3),
+        NodeType::Num(0),
+        NodeType::Num(2),
+        NodeType::Num(isize::MAX - 1),
+        NodeType::Num(isize::MAX),
+        NodeType::Fnum64(f64::NEG_INFINITY),
+        NodeType::Fnum64(-(2f64.powi(63))),
+        NodeType::Fnum64(-3.5),
+        NodeType::Fnum64(-0.0),
+        NodeType::Fnum64(2.0),
+        NodeType::Fnum64(2.5),
+        NodeType::Fnum64(big),
+        NodeType::Fnum64(f64::INFINITY),
+        NodeType::Fnum64(f64::NAN),
+        NodeType::Fnum32(2.0),
+        NodeType::Fnum32(-3.25),
+        NodeType::Text("a".into()),
+    ]
+}
+
+#[test]
+fn mixed_numbers_compare_exactly() {
+    let big = NodeType::Fnum64(isize::MAX as f64);
+    assert!(NodeType::Num(isize::MAX) < big);
+    assert!(NodeType::Num(isize::MAX - 1) < big);
+    assert_ne!(NodeType::Num(isize::MAX), NodeType::Num(isize::MAX - 1));
+
+    assert_eq!(NodeType::Num(2), NodeType::Fnum64(2.0));
+    assert_eq!(NodeType::Num(2), NodeType::Fnum32(2.0));
+    assert_eq!(NodeType::Num(0), NodeType::Fnum64(-0.0));
+    assert!(NodeType::Num(2) < NodeType::Fnum64(2.5));
+    assert!(NodeType::Num(-3) > NodeType::Fnum64(-3.5));
+    assert!(NodeType::Num(isize::MIN) == NodeType::Fnum64(-(2f64.powi(63))));
+    assert!(NodeType::Num(isize::MAX) < NodeType::Fnum64(f64::NAN));
+    assert!(NodeType::Num(isize::MIN) > NodeType::Fnum64(-f64::NAN));
+}
+
+#[test]
+fn ordering_is_a_total_order() {
+    let values = tricky_values();
+    for a in &values {
+        assert_eq!(a.cmp(a), Ordering::Equal, "{:?}", a);
+        for b in &values {
+            assert_eq!(a.cmp(b), b.cmp(a).reverse(), "{:?} {:?}", a, b);
+            for c in &values {
+                if a <= b && b <= c {
+                    assert!(a <= c, "{:?} <= {:?} <= {:?}", a, b, c);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn equal_values_hash_alike() {
+    let values = tricky_values();
+    for a in &values {
+        for b in &values {
+            if a == b {
+                let set: HashSet<&NodeType> = [a, b].into_iter().collect();
+                assert_eq!(set.len(), 1, "{:?} {:?}", a, b);
+            }
+        }
+    }
+    let sorted: BTreeSet<NodeType> = values.into_iter().collect();
+    // Num(2) = Fnum64(2.0) = Fnum32(2.0), Num(0) = -0.0 and Num(MIN) = -2^63 collapse
+    assert_eq!(sorted.len(), 21 - 4);
+}
@@ -0,0 +1,155 @@
+mod common;
+
+use common::{node, round_trip};
+use graphos::models::graph_models::{ConnectionError, Grapho, Node};
+use graphos::models::node_types::NodeType;
+use graphos::models::schema::{EdgeRule, LabelRule, NodeShape, Schema, SchemaError};
+use petgraph::Directed;
+
+type Graph = Grapho<NodeType, Directed>;
+
+fn person(name: &str, age: isize) -> Node<NodeType> {
+    node(name)
+        .with_label("Person")
+        .with_prop("age", NodeType::Num(age))
+}
+
+fn company(name: &str) -> Node<NodeType> {
+    node(name).with_label("Company")
+}
+
+fn strict() -> Graph {
+    let schema = Schema::new()
+        .with_label(
+            "Person",
+            LabelRule::new()
+                .with_content(NodeShape::Text)
+                .with_required("age", NodeShape::Number),
+        )
+        .with_label("Company", LabelRule::new())
+        .with_edge_kind(
+            "works_at",
+            EdgeRule::between("Person", "Company").with_max_out(1),
+        )
+        .deny_other_edge_kinds()
+        .deny_unlabeled();
+    let mut g = Graph::new("strict");
+    g.set_schema(Some(schema));
+    g.add_connection(person("ana", 30), company("acme"), "works_at", "")
+        .unwrap();
+    g
+}
+
+fn rejection(result: Result<(), ConnectionError>) -> SchemaError {
+    match result {
+        Err(ConnectionError::Schema(e)) => e,
+        other => panic!("expected a schema error, got {:?}", other),
+    }
+}
+
+#[test]
+fn connections_breaking_the_schema_are_rejected() {
+    let mut g = strict();
+    // re-adding the same edge does not count against max_out
+    g.add_connection(person("ana", 30), company("acme"), "works_at", "")
+        .unwrap();
+
+    let cases = [
+        (person("ana", 30), company("globex"), "works_at"),
+        (company("acme"), person("bob", 3), "works_at"),
+        (person("bob", 3), person("cid", 3), "knows"),
+        (node("x").with_label("Person"), company("acme"), "works_at"),
+        (node("x"), company("acme"), "works_at"),
+        (node("x").with_label("Robot"), company("acme"), "works_at"),
+        (
+            person("y", 1).with_prop("age", NodeType::Text("one".into())),
+            company("acme"),
+            "works_at",
+        ),
+        (
+            Node::new("z", NodeType::Num(1), "")
+                .with_label("Person")
+                .with_prop("age", NodeType::Num(1)),
+            company("acme"),
+            "works_at",
+        ),
+    ];
+    let errors: Vec<SchemaError> = cases
+        .into_iter()
+        .map(|(from, to, kind)| rejection(g.add_connection(from, to, kind, "")))
+        .collect();
+    assert!(matches!(
+        errors[0],
+        SchemaError::Cardinality { limit: 1, .. }
+    ));
+    assert!(matches!(errors[1], SchemaError::EndpointLabels { .. }));
+    assert!(matches!(errors[2], SchemaError::EdgeKindNotAllowed { .. }));
+    assert!(matches!(errors[3], SchemaError::MissingProperty { .. }));
+    assert!(matches!(errors[4], SchemaError::MissingLabel { .. }));
+    assert!(matches!(errors[5], SchemaError::LabelNotAllowed { .. }));
+    assert!(matches!(errors[6], SchemaError::PropertyShape { .. }));
+    assert!(matches!(errors[7], SchemaError::ContentShape { .. }));
+
+    // rejected connections leave nothing behind
+    assert_eq!(g.core.node_count(), 2);
+    assert_eq!(g.core.edge_count(), 1);
+    assert!(g.validate().is_ok());
+}
+
+#[test]
+fn validate_reports_every_violation_after_reload() {
+    let mut g = strict();
+    let ana = g.get_node_index_by_name("ana").unwrap();
+    g.remove_prop(ana, "age");
+    g.add_label(ana, "Alien").unwrap();
+    let loaded = round_trip(&g, "schema");
+
+    let errors = loaded.validate().unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert!(errors.contains(&SchemaError::MissingProperty {
+        node: "ana".into(),
+        label: "Person".into(),
+        key: "age".into(),
+    }));
+    assert!(errors.contains(&SchemaError::LabelNotAllowed {
+        node: "ana".into(),
+        label: "Alien".into(),
+    }));
+}
+
+#[test]
+fn missing_allow_flags_default_to_allowing() {
+    let schema: Schema = serde_json::from_str(r#"{"labels": {"Person": {}}}"#).unwrap();
+    assert!(schema.allow_unlabeled);
+    assert!(schema.allow_other_edge_kinds);
+
+    let mut g = Graph::new("lenient");
+    g.set_schema(Some(schema));
+    g.add_connection(node("a"), node("b"), "anything", "")
+        .unwrap();
+    assert!(g.validate().is_ok());
+}
+
+#[test]
+fn embedding_mismatch_is_not_a_schema_error() {
+    let mut g = Graph::new("vectors");
+    g.add_connection(
+        node("a").with_embedding(vec![1.0, 0.0]),
+        node("b").with_embedding(vec![0.0, 1.0]),
+        "near",
+        "",
+    )
+    .unwrap();
+    let err = g
+        .add_connection(node("c").with_embedding(vec![1.0]), node("a"), "near", "")
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ConnectionError::EmbeddingDimensions {
+            node: "c".into(),
+            expected: 2,
+            found: 1,
+        }
+    );
+    assert!(String::from(err).starts_with("Error to add connection: node c"));
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use common::temp_path;
+use graphos::models::graph_models::{Grapho, Node};
+use graphos::models::node_types::NodeType;
+use petgraph::Undirected;
+
+type Graph = Grapho<NodeType, Undirected>;
+
+fn person(name: &str, text: &str) -> Node<NodeType> {
+    Node::new(name, NodeType::Text(text.into()), "")
+}
+
+fn people() -> Graph {
+    let mut g = Graph::new("people");
+    g.add_connection(
+        person("p1", "filipe"),
+        person("p2", "maria"),
+        "friendship",
+        "",
+    )
+    .unwrap();
+    g.add_connection(
+        person("p2", "maria"),
+        person("p3", "joao"),
+        "friendship",
+        "",
+    )
+    .unwrap();
+    g
+}
+
+fn hits(g: &Graph, query: &str) -> Vec<String> {
+    g.search(query).into_iter().map(|(r, _)| r.name).collect()
+}
+
+#[test]
+fn search_follows_set_content() {
+    let mut g = people();
+    assert_eq!(hits(&g, "maria"), ["p2"]);
+    assert_eq!(hits(&g, "mar"), ["p2"]);
+
+    let p2 = g.get_node_index_by_name("p2").unwrap();
+    g.set_content(p2, NodeType::Text("henrique".into()))
+        .unwrap();
+    assert!(hits(&g, "maria").is_empty());
+    assert_eq!(hits(&g, "henrique"), ["p2"]);
+}
+
+#[test]
+fn search_reflects_the_file_as_loaded() {
+    let g = people();
+    let path = temp_path("search");
+    g.save_to_file(&path).unwrap();
+    let edited = std::fs::read_to_string(&path)
+        .unwrap()
+        .replace("maria", "henrique");
+    std::fs::write(&path, edited).unwrap();
+    let loaded = Graph::load_from_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(hits(&loaded, "maria").is_empty());
+    assert_eq!(hits(&loaded, "henrique"), ["p2"]);
+    assert_eq!(hits(&loaded, "joao filipe").len(), 2);
+}
+
+#[test]
+fn persisted_index_is_rebuilt_when_node_text_changed() {
+    let g = people();
+    let path = temp_path("search-stale");
+    g.save_to_file(&path).unwrap();
+    let mut json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert!(json["text_index"]["postings"]["maria"].is_object());
+    // edit the node only, leaving the saved index pointing at "maria"
+    let nodes = json["core"]["nodes"].as_array_mut().unwrap();
+    let p2 = nodes.iter_mut().find(|n| n["name"] == "p2").unwrap();
+    p2["content"] = serde_json::to_value(NodeType::Text("henrique".into())).unwrap();
+    std::fs::write(&path, json.to_string()).unwrap();
+    let loaded = Graph::load_from_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(hits(&loaded, "maria").is_empty());
+    assert_eq!(hits(&loaded, "henrique"), ["p2"]);
+}
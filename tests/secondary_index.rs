@@ -0,0 +1,129 @@
+mod common;
+
+use common::{names, node, round_trip};
+use graphos::models::graph_models::{Grapho, Node};
+use graphos::models::node_types::NodeType;
+use graphos::models::secondary_index::{IndexKind, IndexTarget};
+use petgraph::Undirected;
+
+type Graph = Grapho<NodeType, Undirected>;
+
+fn num(name: &str, content: NodeType, city: &str) -> Node<NodeType> {
+    Node::new(name, content, "").with_prop("city", NodeType::Text(city.into()))
+}
+
+// Numeric contents around 10..=20 plus two text ones, each with a city prop.
+fn sample() -> Graph {
+    let mut g = Graph::new("g");
+    for (from, to) in [
+        (
+            num("a", NodeType::Num(5), "lisbon"),
+            num("b", NodeType::Num(10), "london"),
+        ),
+        (
+            num("b", NodeType::Num(10), "london"),
+            num("c", NodeType::Fnum64(12.5), "lisbon"),
+        ),
+        (
+            num("c", NodeType::Fnum64(12.5), "lisbon"),
+            num("d", NodeType::Num(20), "porto"),
+        ),
+        (
+            num("d", NodeType::Num(20), "porto"),
+            num("e", NodeType::Num(21), "london"),
+        ),
+    ] {
+        g.add_connection(from, to, "next", "").unwrap();
+    }
+    g.add_connection(node("e"), node("x"), "next", "").unwrap();
+    g.add_connection(node("x"), node("y"), "next", "").unwrap();
+    g.create_index("content", IndexTarget::Content, IndexKind::Ordered)
+        .unwrap();
+    g.create_index("city", IndexTarget::Prop("city".into()), IndexKind::Hash)
+        .unwrap();
+    g
+}
+
+fn text(value: &str) -> NodeType {
+    NodeType::Text(value.into())
+}
+
+#[test]
+fn hash_index_answers_equality_only() {
+    let g = sample();
+    assert_eq!(
+        names(g.lookup("city", &text("lisbon")).unwrap()),
+        ["a", "c"]
+    );
+    assert!(g.lookup("city", &text("paris")).unwrap().is_empty());
+    assert!(g.lookup_range("city", text("a")..).is_err());
+    assert!(g.lookup_prefix("city", "l").is_err());
+    assert!(g.lookup("missing", &text("lisbon")).is_err());
+}
+
+#[test]
+fn ordered_index_answers_ranges_and_prefixes() {
+    let g = sample();
+    let between = g
+        .lookup_range("content", NodeType::Num(10)..=NodeType::Num(20))
+        .unwrap();
+    assert_eq!(names(between), ["b", "c", "d"]);
+    // integers and floats are equal by value
+    let ten = g.lookup("content", &NodeType::Fnum64(10.0)).unwrap();
+    assert_eq!(names(ten), ["b"]);
+
+    // open ranges keep to the type of their bound, text sorts above numbers
+    let above = g.lookup_range("content", NodeType::Num(20)..).unwrap();
+    assert_eq!(names(above), ["d", "e"]);
+    let below = g.lookup_range("content", ..text("y")).unwrap();
+    assert_eq!(names(below), ["x"]);
+
+    assert_eq!(names(g.lookup_prefix("content", "").unwrap()), ["x", "y"]);
+    assert_eq!(names(g.lookup_prefix("content", "y").unwrap()), ["y"]);
+    assert!(
+        g.lookup_range("content", NodeType::Num(20)..NodeType::Num(10))
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn indexes_follow_node_changes() {
+    let mut g = sample();
+    let a = g.get_node_index_by_name("a").unwrap();
+    g.set_content(a, NodeType::Num(15)).unwrap();
+    g.set_prop(a, "city", text("porto")).unwrap();
+    let between = g
+        .lookup_range("content", NodeType::Num(10)..=NodeType::Num(20))
+        .unwrap();
+    assert_eq!(names(between), ["a", "b", "c", "d"]);
+    assert!(g.lookup("content", &NodeType::Num(5)).unwrap().is_empty());
+    assert_eq!(names(g.lookup("city", &text("porto")).unwrap()), ["a", "d"]);
+
+    g.remove_prop(a, "city");
+    assert_eq!(names(g.lookup("city", &text("porto")).unwrap()), ["d"]);
+
+    g.add_connection(node("y"), num("z", NodeType::Num(11), "porto"), "next", "")
+        .unwrap();
+    assert_eq!(names(g.lookup("city", &text("porto")).unwrap()), ["d", "z"]);
+    let z = g.lookup("content", &NodeType::Num(11)).unwrap();
+    assert_eq!(names(z), ["z"]);
+}
+
+#[test]
+fn entries_are_rebuilt_after_load() {
+    let mut g = sample();
+    let a = g.get_node_index_by_name("a").unwrap();
+    g.set_content(a, NodeType::Num(15)).unwrap();
+
+    let loaded = round_trip(&g, "secondary-index");
+    assert_eq!(loaded.index_names(), ["city", "content"]);
+    let between = loaded
+        .lookup_range("content", NodeType::Num(10)..=NodeType::Num(20))
+        .unwrap();
+    assert_eq!(names(between), ["a", "b", "c", "d"]);
+    assert_eq!(
+        names(loaded.lookup("city", &text("london")).unwrap()),
+        ["b", "e"]
+    );
+}
@@ -0,0 +1,141 @@
+mod common;
+
+use common::node;
+use graphos::algorithms::set_ops::NodeIdentity;
+use graphos::models::graph_models::Grapho;
+use graphos::models::node_types::NodeType;
+use petgraph::Undirected;
+
+type Graph = Grapho<NodeType, Undirected>;
+
+// `links` parallel a-b edges, plus one b-c edge when `with_bc`.
+fn multigraph(name: &str, links: usize, with_bc: bool) -> Graph {
+    let mut g = Graph::new(name);
+    for _ in 0..links {
+        g.add_parallel_connection(node("a"), node("b"), "link", "")
+            .unwrap();
+    }
+    if with_bc {
+        g.add_connection(node("b"), node("c"), "link", "").unwrap();
+    }
+    g
+}
+
+fn counts(g: &Graph) -> (usize, usize) {
+    (g.core.node_count(), g.core.edge_count())
+}
+
+fn connections(g: &Graph, from: &str, to: &str) -> usize {
+    let a = g.get_node_index_by_name(from).unwrap();
+    let b = g.get_node_index_by_name(to).unwrap();
+    g.connections_between(a, b).len()
+}
+
+#[test]
+fn parallel_edges_behave_as_multisets() {
+    let two = multigraph("two", 2, false);
+    let three = multigraph("three", 3, true);
+
+    let union = two.union(&three, NodeIdentity::Name).unwrap();
+    assert_eq!(counts(&union), (3, 4));
+    assert_eq!(connections(&union, "a", "b"), 3);
+
+    let intersection = two.intersection(&three, NodeIdentity::Name).unwrap();
+    assert_eq!(counts(&intersection), (2, 2));
+    assert_eq!(connections(&intersection, "a", "b"), 2);
+
+    let difference = three.difference(&two, NodeIdentity::Name).unwrap();
+    assert_eq!(counts(&difference), (3, 2));
+    assert_eq!(connections(&difference, "a", "b"), 1);
+    assert_eq!(connections(&difference, "b", "c"), 1);
+
+    assert_eq!(
+        counts(&two.difference(&three, NodeIdentity::Name).unwrap()),
+        (0, 0)
+    );
+}
+
+#[test]
+fn edge_instances_keep_their_ids_and_data() {
+    let mut g = multigraph("g", 2, false);
+    let a = g.get_node_index_by_name("a").unwrap();
+    let b = g.get_node_index_by_name("b").unwrap();
+    let instance = g.connections_between(a, b)[0].instance.unwrap();
+    g.set_edge_prop(instance, "weight", NodeType::Num(7))
+        .unwrap();
+
+    let union = g.union(&Graph::new("empty"), NodeIdentity::Name).unwrap();
+    assert_eq!(
+        union.get_edge_prop(instance, "weight"),
+        Some(&NodeType::Num(7))
+    );
+}
+
+#[test]
+fn uuid_identity_refuses_to_merge_namesakes() {
+    let first = multigraph("first", 1, false);
+    let second = multigraph("second", 1, true);
+
+    let err = first.union(&second, NodeIdentity::Uuid).unwrap_err();
+    assert!(err.contains("name a"), "{}", err);
+
+    // no shared ids: nothing in common, nothing removed
+    assert_eq!(
+        counts(&first.intersection(&second, NodeIdentity::Uuid).unwrap()),
+        (0, 0)
+    );
+    assert_eq!(
+        counts(&first.difference(&second, NodeIdentity::Uuid).unwrap()),
+        (2, 1)
+    );
+}
+
+#[test]
+fn uuid_identity_matches_copies_of_the_same_graph() {
+    let base = multigraph("base", 2, false);
+    let mut copy = base.subgraph(base.core.node_indices());
+    copy.add_connection(node("b"), node("c"), "link", "")
+        .unwrap();
+    let a = copy.get_node_index_by_name("a").unwrap();
+    copy.core[a].name = "renamed".into();
+
+    let union = base.union(&copy, NodeIdentity::Uuid).unwrap();
+    // shared nodes come from `base`, so "a" keeps its name
+    assert_eq!(counts(&union), (3, 3));
+    assert!(union.get_node_index_by_name("a").is_some());
+    assert_eq!(
+        counts(&copy.difference(&base, NodeIdentity::Uuid).unwrap()),
+        (2, 1)
+    );
+}
+
+#[test]
+fn uuid_identity_matches_parallel_edges_by_instance() {
+    let base = multigraph("base", 2, false);
+    let (mut ours, mut theirs) = (
+        base.subgraph(base.core.node_indices()),
+        base.subgraph(base.core.node_indices()),
+    );
+    let added = ours
+        .add_parallel_connection(node("a"), node("b"), "link", "")
+        .unwrap();
+    theirs
+        .add_parallel_connection(node("a"), node("b"), "link", "")
+        .unwrap();
+
+    // by name both sides hold three a-b links; by uuid only two are shared
+    let union = ours.union(&theirs, NodeIdentity::Uuid).unwrap();
+    assert_eq!(connections(&union, "a", "b"), 4);
+    let union = ours.union(&theirs, NodeIdentity::Name).unwrap();
+    assert_eq!(connections(&union, "a", "b"), 3);
+
+    let intersection = ours.intersection(&theirs, NodeIdentity::Uuid).unwrap();
+    assert_eq!(connections(&intersection, "a", "b"), 2);
+    let difference = ours.difference(&theirs, NodeIdentity::Uuid).unwrap();
+    assert_eq!(counts(&difference), (2, 1));
+    assert!(difference.edge_data(added).is_some());
+    assert_eq!(
+        counts(&ours.difference(&theirs, NodeIdentity::Name).unwrap()),
+        (0, 0)
+    );
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use common::{names, node};
+use graphos::models::graph_models::Grapho;
+use graphos::models::node_types::NodeType;
+use petgraph::Undirected;
+
+type Graph = Grapho<NodeType, Undirected>;
+
+// Square a-b-c-d with an a-c diagonal, plus a separate x-y colleague pair.
+fn weighted() -> Graph {
+    let mut g = Graph::new("g");
+    for (from, to, kind, weight) in [
+        ("a", "b", "road", 1),
+        ("b", "c", "road", 2),
+        ("c", "d", "road", 3),
+        ("d", "a", "road", 4),
+        ("a", "c", "road", 5),
+        ("x", "y", "colleague", 7),
+    ] {
+        g.add_connection(node(from), node(to), kind, "").unwrap();
+        let (a, b) = (
+            g.get_node_index_by_name(from).unwrap(),
+            g.get_node_index_by_name(to).unwrap(),
+        );
+        let edge = g.core.find_edge(a, b).unwrap();
+        *g.core.edge_weight_mut(edge).unwrap() = weight;
+    }
+    g
+}
+
+fn neighbours(g: &Graph, name: &str) -> Vec<String> {
+    let idx = g.get_node_index_by_name(name).unwrap();
+    names(g.core[idx].connections.iter().map(|c| c.node.clone()))
+}
+
+#[test]
+fn minimum_spanning_forest_keeps_the_lightest_edges() {
+    let g = weighted();
+    let forest = g.minimum_spanning_forest();
+
+    assert_eq!(forest.name, "g-msf");
+    assert_eq!(forest.core.node_count(), 6);
+    assert_eq!(forest.core.edge_count(), 4);
+    assert_eq!(forest.runtime_ref.edges.len(), 4);
+    let total: u32 = forest.core.edge_weights().sum();
+    assert_eq!(total, 1 + 2 + 3 + 7);
+
+    assert_eq!(neighbours(&forest, "a"), ["b"]);
+    assert_eq!(neighbours(&forest, "c"), ["b", "d"]);
+    assert_eq!(neighbours(&forest, "d"), ["c"]);
+    assert_eq!(neighbours(&forest, "x"), ["y"]);
+    // the source graph is untouched
+    assert_eq!(neighbours(&g, "a"), ["b", "c", "d"]);
+
+    let kinds: Vec<&str> = forest.edge_kinds.iter().map(|k| k.name.as_str()).collect();
+    assert_eq!(kinds, ["road", "colleague"]);
+    for r in &forest.runtime_ref.edges {
+        assert!(g.runtime_ref.edges.iter().any(|o| o.uuid == r.uuid));
+    }
+    for idx in forest.core.node_indices() {
+        let original = g.get_node_index_by_name(&forest.core[idx].name).unwrap();
+        assert_eq!(forest.core[idx].metadata.id, g.core[original].metadata.id);
+    }
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use std::collections::HashMap;
+
+use common::node;
+use graphos::models::graph_models::Grapho;
+use graphos::models::node_types::NodeType;
+use petgraph::Undirected;
+use petgraph::graph::NodeIndex;
+
+type Graph = Grapho<NodeType, Undirected>;
+
+// Triangle a-b-c with d hanging off c.
+fn triangle_plus_pendant() -> Graph {
+    let mut g = Graph::new("g");
+    for (from, to) in [("a", "b"), ("b", "c"), ("c", "a"), ("c", "d")] {
+        g.add_connection(node(from), node(to), "link", "").unwrap();
+    }
+    g
+}
+
+fn per_node<V: Copy>(g: &Graph, values: &HashMap<NodeIndex, V>) -> Vec<V> {
+    ["a", "b", "c", "d"]
+        .iter()
+        .map(|name| values[&g.get_node_index_by_name(name).unwrap()])
+        .collect()
+}
+
+#[test]
+fn triangle_plus_pendant_stats() {
+    let g = triangle_plus_pendant();
+    assert_eq!(g.triangle_count(), 1);
+    assert_eq!(per_node(&g, &g.triangle_counts()), [1, 1, 1, 0]);
+    assert_eq!(
+        per_node(&g, &g.clustering_coefficients()),
+        [1.0, 1.0, 1.0 / 3.0, 0.0]
+    );
+    assert!((g.average_clustering() - 7.0 / 12.0).abs() < 1e-12);
+    // 3 closed triplets out of 1 + 1 + 3 + 0
+    assert!((g.global_clustering() - 0.6).abs() < 1e-12);
+    assert_eq!(per_node(&g, &g.core_numbers()), [2, 2, 2, 1]);
+
+    let stats = g.stats();
+    assert_eq!((stats.node_count, stats.edge_count), (4, 4));
+    assert_eq!(stats.triangles, 1);
+    assert_eq!(stats.degeneracy, 2);
+    let d = stats.nodes.iter().find(|n| n.name == "d").unwrap();
+    assert_eq!((d.degree, d.triangles, d.core_number), (1, 0, 1));
+    assert_eq!(stats.edge_kinds, [("link".to_string(), 4.0)]);
+}
+
+#[test]
+fn parallel_edges_and_self_loops_do_not_make_triangles() {
+    let mut g = triangle_plus_pendant();
+    g.add_parallel_connection(node("a"), node("b"), "link", "")
+        .unwrap();
+    g.add_connection(node("d"), node("d"), "link", "").unwrap();
+    assert_eq!(g.triangle_count(), 1);
+    assert_eq!(per_node(&g, &g.core_numbers()), [2, 2, 2, 1]);
+}
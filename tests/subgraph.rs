@@ -0,0 +1,97 @@
+mod common;
+
+use common::{names, node, round_trip};
+use graphos::models::graph_models::Grapho;
+use graphos::models::node_types::NodeType;
+use petgraph::Undirected;
+use petgraph::graph::NodeIndex;
+
+type Graph = Grapho<NodeType, Undirected>;
+
+// a - b - c - e, with d hanging off b; c-e is the only colleague edge.
+fn sample() -> Graph {
+    let mut g = Graph::new("people");
+    for (from, to, kind) in [
+        ("a", "b", "friendship"),
+        ("b", "c", "friendship"),
+        ("b", "d", "friendship"),
+        ("c", "e", "colleague"),
+    ] {
+        g.add_connection(node(from), node(to), kind, "").unwrap();
+    }
+    g
+}
+
+fn idx(g: &Graph, name: &str) -> NodeIndex {
+    g.get_node_index_by_name(name).unwrap()
+}
+
+fn energy(g: &Graph, name: &str) -> f64 {
+    g.core[idx(g, name)].energy
+}
+
+fn kinds(g: &Graph) -> Vec<(String, f64)> {
+    g.edge_kinds
+        .iter()
+        .map(|k| (k.name.clone(), k.energy))
+        .collect()
+}
+
+#[test]
+fn ego_graph_is_a_self_contained_grapho() {
+    let g = sample();
+    let ego = g.ego_graph(idx(&g, "a"), 1).unwrap();
+
+    assert_eq!(ego.name, "people-ego-a");
+    assert_eq!(names(ego.runtime_ref.nodes.clone()), ["a", "b"]);
+    assert_eq!(ego.runtime_ref.edges.len(), 1);
+    assert_eq!(ego.core.edge_count(), 1);
+    let kind_refs: Vec<&str> = ego
+        .runtime_ref
+        .edge_kinds
+        .iter()
+        .map(|k| k.name.as_str())
+        .collect();
+    assert_eq!(kind_refs, ["friendship"]);
+    assert_eq!(kinds(&ego), [("friendship".to_string(), 1.0)]);
+
+    // b had three connections in the source graph, one survives
+    assert_eq!(energy(&g, "b"), 3.0);
+    assert_eq!(energy(&ego, "b"), 1.0);
+    let b = idx(&ego, "b");
+    assert_eq!(ego.core[b].connections.len(), 1);
+    assert_eq!(ego.core[b].connections[0].node.index, idx(&ego, "a"));
+    assert_eq!(ego.core[b].metadata.id, g.core[idx(&g, "b")].metadata.id);
+}
+
+#[test]
+fn ego_graph_grows_with_k() {
+    let g = sample();
+    let two = g.ego_graph(idx(&g, "a"), 2).unwrap();
+    assert_eq!(names(two.runtime_ref.nodes.clone()), ["a", "b", "c", "d"]);
+    assert_eq!(two.core.edge_count(), 3);
+    assert_eq!(kinds(&two), [("friendship".to_string(), 3.0)]);
+    assert_eq!(energy(&two, "c"), 1.0);
+
+    let all = g.ego_graph(idx(&g, "a"), 3).unwrap();
+    assert_eq!(all.core.node_count(), 5);
+    assert_eq!(kinds(&all), kinds(&g));
+
+    assert_eq!(g.ego_graph(idx(&g, "a"), 0).unwrap().core.node_count(), 1);
+    assert!(g.ego_graph(NodeIndex::new(99), 1).is_err());
+}
+
+#[test]
+fn subgraph_keeps_only_induced_edges_and_their_kinds() {
+    let g = sample();
+    let sub = g.subgraph([idx(&g, "c"), idx(&g, "e"), idx(&g, "a")]);
+    assert_eq!(sub.core.node_count(), 3);
+    assert_eq!(sub.core.edge_count(), 1);
+    assert_eq!(kinds(&sub), [("colleague".to_string(), 1.0)]);
+    assert_eq!(energy(&sub, "a"), 0.0);
+    assert!(sub.core[idx(&sub, "a")].connections.is_empty());
+
+    let loaded = round_trip(&sub, "subgraph");
+    assert_eq!(names(loaded.runtime_ref.nodes.clone()), ["a", "c", "e"]);
+    assert_eq!(kinds(&loaded), kinds(&sub));
+}
@@ -0,0 +1,193 @@
+mod common;
+
+use std::collections::HashSet;
+
+use common::round_trip;
+use graphos::algorithms::merge::MergeStrategy;
+use graphos::algorithms::set_ops::NodeIdentity;
+use graphos::models::graph_models::{ConnectionError, Grapho, Node};
+use graphos::models::node_types::{NodeType, NodeTypes};
+use graphos::models::vector_index::{VectorIndex, VectorMetric};
+use petgraph::Undirected;
+use petgraph::graph::NodeIndex;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+const DIMENSIONS: usize = 16;
+
+fn random_vector(rng: &mut StdRng) -> Vec<f32> {
+    (0..DIMENSIONS).map(|_| rng.gen_range(-1.0..1.0)).collect()
+}
+
+fn exact_top_k(items: &[(NodeIndex, Vec<f32>)], query: &[f32], k: usize) -> HashSet<NodeIndex> {
+    let mut scored: Vec<(NodeIndex, f32)> = items
+        .iter()
+        .map(|(idx, v)| (*idx, VectorMetric::Cosine.distance(query, v)))
+        .collect();
+    scored.sort_by(|a, b| a.1.total_cmp(&b.1));
+    scored.into_iter().take(k).map(|(idx, _)| idx).collect()
+}
+
+#[test]
+fn hnsw_recall_matches_exact_scan() {
+    let mut rng = StdRng::seed_from_u64(7);
+    let items: Vec<(NodeIndex, Vec<f32>)> = (0..500)
+        .map(|i| (NodeIndex::new(i), random_vector(&mut rng)))
+        .collect();
+    let mut index = VectorIndex::new(VectorMetric::Cosine);
+    for (idx, v) in &items {
+        index.insert(*idx, v.clone()).unwrap();
+    }
+    assert_eq!(index.len(), 500);
+
+    let (k, queries) = (10, 50);
+    let mut found = 0;
+    for _ in 0..queries {
+        let query = random_vector(&mut rng);
+        let exact = exact_top_k(&items, &query, k);
+        let approx = index.search(&query, k);
+        assert_eq!(approx.len(), k);
+        found += approx.iter().filter(|(idx, _)| exact.contains(idx)).count();
+    }
+    let recall = found as f64 / (k * queries) as f64;
+    assert!(recall >= 0.9, "recall {}", recall);
+}
+
+#[test]
+fn rejects_mismatched_dimensions() {
+    let mut index = VectorIndex::new(VectorMetric::L2);
+    index.insert(NodeIndex::new(0), vec![1.0, 0.0]).unwrap();
+    assert!(
+        index
+            .insert(NodeIndex::new(1), vec![1.0, 0.0, 0.0])
+            .is_err()
+    );
+    assert_eq!(index.dimensions(), Some(2));
+}
+
+#[test]
+fn new_endpoints_must_agree_on_dimensions() {
+    let mut g = Grapho::<NodeType, Undirected>::new("vectors");
+    let a = Node::new("a", NodeType::Num(1), "").with_embedding(vec![1.0, 0.0]);
+    let b = Node::new("b", NodeType::Num(2), "").with_embedding(vec![1.0, 0.0, 0.0]);
+    assert_eq!(
+        g.add_connection(a.clone(), b.clone(), "next", ""),
+        Err(ConnectionError::EmbeddingDimensions {
+            node: "b".into(),
+            expected: 2,
+            found: 3,
+        })
+    );
+    assert_eq!(g.core.node_count(), 0);
+
+    // set operations and merges refuse to mix sizes as well
+    let mut other = Grapho::<NodeType, Undirected>::new("other");
+    let c = Node::new("c", NodeType::Num(3), "").with_embedding(vec![0.0, 1.0, 0.0]);
+    other.add_connection(b, c, "next", "").unwrap();
+    let d = Node::new("d", NodeType::Num(4), "").with_embedding(vec![0.0, 1.0]);
+    g.add_connection(a, d, "next", "").unwrap();
+    assert!(g.union(&other, NodeIdentity::Name).is_err());
+    let empty = Grapho::new("empty");
+    assert!(Grapho::merge(&empty, &g, &other, MergeStrategy::Ours).is_err());
+}
+
+fn embedded_graph() -> Grapho<NodeType, Undirected> {
+    let mut rng = StdRng::seed_from_u64(11);
+    let mut g = Grapho::<NodeType, Undirected>::new("vectors");
+    for i in 0..100 {
+        let a = Node::new(&format!("n{}", i), NodeType::Num(i), "")
+            .with_embedding(random_vector(&mut rng));
+        let b = Node::new(&format!("n{}", i + 1), NodeType::Num(i + 1), "")
+            .with_embedding(random_vector(&mut rng));
+        g.add_connection(a, b, "next", "").unwrap();
+    }
+    g
+}
+
+fn nearest_names(g: &Grapho<NodeType, Undirected>, query: &[f32]) -> Vec<String> {
+    g.nearest_nodes(query, 5, VectorMetric::Cosine)
+        .into_iter()
+        .map(|(r, _)| r.name)
+        .collect()
+}
+
+#[test]
+fn index_is_restored_after_load() {
+    let g = embedded_graph();
+    let loaded = round_trip(&g, "vectors");
+
+    let mut rng = StdRng::seed_from_u64(3);
+    for _ in 0..10 {
+        let query = random_vector(&mut rng);
+        assert_eq!(nearest_names(&g, &query), nearest_names(&loaded, &query));
+    }
+    assert_eq!(loaded.vector_metric(), VectorMetric::Cosine);
+}
+
+#[test]
+fn node_embedding_wins_the_query_it_equals() {
+    let mut g = embedded_graph();
+    let n42 = g.get_node_index_by_name("n42").unwrap();
+    let query = g.core[n42].embedding.clone().unwrap();
+    assert_eq!(nearest_names(&g, &query)[0], "n42");
+
+    let target: Vec<f32> = (0..DIMENSIONS).map(|i| i as f32).collect();
+    g.set_node_embedding(n42, target.clone()).unwrap();
+    let (best, score) = g.nearest_nodes(&target, 1, VectorMetric::Cosine)[0].clone();
+    assert_eq!(best.name, "n42");
+    assert!((score - 1.0).abs() < 1e-5);
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum Doc {
+    Plain(String),
+    Embedded(Vec<f32>),
+}
+
+impl NodeTypes for Doc {
+    fn embedding(&self) -> Option<Vec<f32>> {
+        match self {
+            Doc::Plain(_) => None,
+            Doc::Embedded(v) => Some(v.clone()),
+        }
+    }
+}
+
+#[test]
+fn set_content_refreshes_content_embeddings() {
+    let mut g = Grapho::<Doc, Undirected>::new("docs");
+    let doc = |name: &str, content: Doc| Node::new(name, content, "");
+    g.add_connection(
+        doc("a", Doc::Embedded(vec![1.0, 0.0])),
+        doc("b", Doc::Embedded(vec![0.0, 1.0])),
+        "cites",
+        "",
+    )
+    .unwrap();
+    let nearest = |g: &Grapho<Doc, Undirected>, query: &[f32]| {
+        g.nearest_nodes(query, 1, VectorMetric::Cosine)[0]
+            .0
+            .name
+            .clone()
+    };
+    assert_eq!(nearest(&g, &[-1.0, 0.1]), "b");
+
+    let a = g.get_node_index_by_name("a").unwrap();
+    g.set_content(a, Doc::Embedded(vec![-1.0, 0.0])).unwrap();
+    assert_eq!(g.core[a].embedding, Some(vec![-1.0, 0.0]));
+    assert_eq!(nearest(&g, &[-1.0, 0.1]), "a");
+
+    assert!(g.set_content(a, Doc::Embedded(vec![1.0])).is_err());
+    assert_eq!(g.core[a].embedding, Some(vec![-1.0, 0.0]));
+
+    // content without an embedding takes the derived one with it
+    let b = g.get_node_index_by_name("b").unwrap();
+    g.set_content(b, Doc::Plain("b".into())).unwrap();
+    g.set_content(a, Doc::Plain("a".into())).unwrap();
+    assert_eq!(g.core[a].embedding, None);
+    assert!(
+        g.nearest_nodes(&[0.0, 1.0], 1, VectorMetric::Cosine)
+            .is_empty()
+    );
+}